@@ -0,0 +1,67 @@
+// compares Database::find_candidates with a linear scan over all subcells
+// run with `cargo bench`
+
+extern crate libsrl;
+use std::time::Instant;
+use libsrl::db::Database;
+use libsrl::db::dtree::{is_candidate, is_indexed};
+use libsrl::cell::Cell;
+
+const RULES : usize = 5000;
+const QUERIES : usize = 200;
+
+// simple cells may not contain digits
+fn name(prefix : &str, mut i : usize) -> String {
+	let mut string = prefix.to_string();
+	loop {
+		string.push((b'a' + (i % 26) as u8) as char);
+		i /= 26;
+		if i == 0 { return string; }
+	}
+}
+
+fn create_db() -> Database {
+	let mut string = String::new();
+	for i in 0..RULES {
+		string.push_str(&format!("{{0 (= ({} 0 {}) ({} (h 0) b))}}.\n", name("f", i % 97), name("a", i % 13), name("g", i % 31)));
+	}
+	Database::by_string(&string).unwrap()
+}
+
+fn linear_scan(db : &Database, term : &Cell) -> usize {
+	let mut count = 0;
+	for rule_id in 0..db.count_rules() {
+		let rule = db.get_rule_ref(rule_id).unwrap();
+		count += rule.pre_order().filter(|&(cell, _)| is_indexed(cell) && is_candidate(term, cell)).count();
+	}
+	count
+}
+
+fn main() {
+	let start = Instant::now();
+	let db = create_db();
+	println!("building database with {} rules (incl. indices): {:?}", RULES, start.elapsed());
+
+	let terms : Vec<Cell> = (0..QUERIES)
+		.map(|i| Cell::by_string(&format!("{} (h x) {}", name("f", i % 97), name("a", i % 13))).unwrap())
+		.collect();
+
+	let start = Instant::now();
+	let mut tree_count = 0;
+	for term in &terms {
+		tree_count += db.find_candidates(term).len();
+	}
+	let tree_time = start.elapsed();
+
+	let start = Instant::now();
+	let mut scan_count = 0;
+	for term in &terms {
+		scan_count += linear_scan(&db, term);
+	}
+	let scan_time = start.elapsed();
+
+	assert_eq!(tree_count, scan_count);
+	println!("{} queries, {} candidates", QUERIES, tree_count);
+	println!("discrimination tree: {:?}", tree_time);
+	println!("linear scan:         {:?}", scan_time);
+}
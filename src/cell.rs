@@ -1,13 +1,13 @@
 use std::fmt;
-use error::SRLError;
+use error::{SRLError, CellErrorKind, NavigationErrorKind};
 use parse::SIMPLE_CELL_FILL_CHARS;
 use parse::SIMPLE_CELL_CHARS;
 use misc::*;
 
-#[derive(PartialEq, Clone)]
+#[derive(PartialEq, Eq, Hash, Clone)]
 pub struct SimpleString(String);
 
-#[derive(PartialEq, Clone)]
+#[derive(PartialEq, Eq, Hash, Clone)]
 pub enum Cell {
 	Simple { string : SimpleString },
 	Complex { cells: Vec<Cell> },
@@ -16,16 +16,16 @@ pub enum Cell {
 	Case { condition : Box<Cell>, conclusion : Box<Cell> }
 }
 
-#[derive(PartialEq)]
+#[derive(PartialEq, Clone, Debug)]
 pub enum CellType { Simple, Complex, Scope, Var, Case }
 
 impl SimpleString {
 	pub fn create(string : String) -> Result<SimpleString, SRLError> {
 		if string.len() == 0 {
-			return Err(SRLError("SimpleString::create".to_string(), "string has length 0".to_string()));
+			return Err(SRLError::invalid_cell("SimpleString::create", CellErrorKind::EmptySimpleString));
 		}
 		if !contains_only(string.clone(), SIMPLE_CELL_CHARS.to_string()) {
-			return Err(SRLError("SimpleString::create".to_string(), "invalid char".to_string()));
+			return Err(SRLError::invalid_cell("SimpleString::create", CellErrorKind::InvalidChar));
 		}
 		if string == "=" || contains_only(string.clone(), SIMPLE_CELL_FILL_CHARS.to_string()) {
 			return Ok(SimpleString(string));
@@ -33,7 +33,7 @@ impl SimpleString {
 		if string.starts_with('\'') && string.ends_with('\'') && string.matches('\'').count() == 2 {
 			return Ok(SimpleString(string));
 		}
-		return Err(SRLError("SimpleString::create".to_string(), "weird invalid stuff".to_string()));
+		return Err(SRLError::invalid_cell("SimpleString::create", CellErrorKind::InvalidSimpleString));
 	}
 
 	pub fn get_string(&self) -> String { self.0.clone() }
@@ -58,17 +58,17 @@ impl Cell {
 		use parse::tokenize::*;
 
 		if string.contains('.') {
-			return Err(SRLError("Cell::by_string".to_string(), "string contains '.'".to_string()));
+			return Err(SRLError::parse("Cell::by_string", "string contains '.'"));
 		}
 
 		match find_invalid_char(string) {
-			Some(_) => return Err(SRLError("Cell::by_string".to_string(), "invalid char".to_string())),
+			Some(_) => return Err(SRLError::parse("Cell::by_string", "invalid char")),
 			None => {}
 		}
 		let string : String = fix_whitespaces(string);
 		let tokens = tokenize(string)?;
 		if ! check_paren_correctness(tokens.clone()) {
-			return Err(SRLError("Cell::by_string".to_string(), "parens incorrect".to_string()));
+			return Err(SRLError::parse("Cell::by_string", "parens incorrect"));
 		}
 		assemble(tokens)
 	}
@@ -93,13 +93,8 @@ impl Cell {
 		match &self {
 			&&Cell::Simple { string : ref string_out } => string_out.0.clone(),
 			&&Cell::Complex { cells : ref cells_out } => {
-				let mut string = String::new();
-				string.push_str(&cells_out[0].to_string());
-				for cell in cells_out.iter().skip(1) {
-					string.push(' ');
-					string.push_str(&cell.to_string());
-				}
-				return string;
+				let strings : Vec<String> = cells_out.iter().map(|x| x.to_string()).collect();
+				return strings.join(" ");
 			},
 			&&Cell::Scope { id : ref id_out, body : ref body_out } => {
 				let mut string = String::new();
@@ -139,65 +134,106 @@ impl Cell {
 		};
 	}
 
+	pub fn try_get_subcell(&self, index : usize) -> Result<Cell, SRLError> {
+		match self.get_subcell_ref(index) {
+			Some(x) => Ok(x.clone()),
+			None => Err(SRLError::navigation("Cell::get_subcell", NavigationErrorKind::InvalidIndex))
+		}
+	}
+
 	pub fn get_subcell(&self, index : usize) -> Cell {
-		return match &self {
-			&&Cell::Simple {..} => panic!("Cell::get_subcell(): Simple: no subcells"),
-			&&Cell::Complex { cells : ref cells_out } => {
-				if ! index_in_len(index, cells_out.len()) {
-					panic!("Cell::get_subcell(): Complex: index out of range")
-				}
-				cells_out[index].clone()
-			}
-			&&Cell::Scope { body : ref body_out, .. } => {
-				if index == 0 {
-					body_out.as_ref().clone()
-				} else {
-					panic!("Cell::get_subcell(): Scope: index out of range")
-				}
-			},
-			&&Cell::Var {..} => panic!("Cell::get_subcell(): Var: no subcells"),
-			&&Cell::Case { condition : ref cond_out, conclusion : ref conc_out } => {
-				if index == 0 {
-					return cond_out.as_ref().clone();
-				}
-				if index == 1 {
-					return conc_out.as_ref().clone();
-				} 
-				panic!("Cell::get_subcell(): Case: index out of range");
-			}
+		match self.try_get_subcell(index) {
+			Ok(x) => x,
+			Err(srl_error) => panic!("{}", srl_error)
+		}
+	}
+
+	// like get_subcell, but borrows instead of cloning
+	pub fn get_subcell_ref(&self, index : usize) -> Option<&Cell> {
+		match &self {
+			&&Cell::Complex { cells : ref cells_out } => cells_out.get(index),
+			&&Cell::Scope { body : ref body_out, .. } if index == 0 => Some(body_out.as_ref()),
+			&&Cell::Case { condition : ref cond_out, .. } if index == 0 => Some(cond_out.as_ref()),
+			&&Cell::Case { conclusion : ref conc_out, .. } if index == 1 => Some(conc_out.as_ref()),
+			_ => None
+		}
+	}
+
+	// crate-internal: through a raw &mut Cell any subcell could become a complex cell with less than 2 subcells
+	// (the invariant of gen::complex), and nothing would check it afterwards. users edit with edit_subcell,
+	// which checks the invariant once f is done (for that it keeps a copy of the old subcell to undo the edit)
+	pub(crate) fn get_subcell_mut(&mut self, index : usize) -> Option<&mut Cell> {
+		match self {
+			&mut Cell::Complex { cells : ref mut cells_out } => cells_out.get_mut(index),
+			&mut Cell::Scope { body : ref mut body_out, .. } if index == 0 => Some(body_out.as_mut()),
+			&mut Cell::Case { condition : ref mut cond_out, .. } if index == 0 => Some(cond_out.as_mut()),
+			&mut Cell::Case { conclusion : ref mut conc_out, .. } if index == 1 => Some(conc_out.as_mut()),
+			_ => None
+		}
+	}
+
+	pub fn path_ref(&self, indices : &[usize]) -> Option<&Cell> {
+		let mut cell = self;
+		for &index in indices {
+			cell = cell.get_subcell_ref(index)?;
+		}
+		Some(cell)
+	}
+
+	// crate-internal like get_subcell_mut
+	pub(crate) fn path_mut(&mut self, indices : &[usize]) -> Option<&mut Cell> {
+		let mut cell = self;
+		for &index in indices {
+			cell = cell.get_subcell_mut(index)?;
+		}
+		Some(cell)
+	}
+
+	// replaces the subcell at indices in place and returns the old one
+	// -- errors like gen::complex on complex cells with less than 2 subcells
+	pub fn replace_subcell(&mut self, indices : &[usize], cell : Cell) -> Result<Cell, SRLError> {
+		if has_short_complex(&cell) {
+			return Err(SRLError::invalid_cell("Cell::replace_subcell", CellErrorKind::ShortComplex));
+		}
+		match self.path_mut(indices) {
+			Some(x) => Ok(::std::mem::replace(x, cell)),
+			None => Err(SRLError::navigation("Cell::replace_subcell", NavigationErrorKind::InvalidIndex))
+		}
+	}
+
+	// edits the subcell at indices in place, f may change it arbitrarily
+	// -- if f leaves a complex cell with less than 2 subcells behind, the edit is undone and an error returned
+	pub fn edit_subcell<F : FnOnce(&mut Cell)>(&mut self, indices : &[usize], f : F) -> Result<(), SRLError> {
+		let subcell = match self.path_mut(indices) {
+			Some(x) => x,
+			None => return Err(SRLError::navigation("Cell::edit_subcell", NavigationErrorKind::InvalidIndex))
 		};
+		let old = subcell.clone();
+		f(subcell);
+		if has_short_complex(subcell) {
+			*subcell = old;
+			return Err(SRLError::invalid_cell("Cell::edit_subcell", CellErrorKind::ShortComplex));
+		}
+		Ok(())
+	}
+
+	pub fn try_with_subcell(&self, cell : Cell, index : usize) -> Result<Cell, SRLError> {
+		let mut new = self.clone();
+		new.replace_subcell(&[index], cell)?;
+		Ok(new)
 	}
 
 	pub fn with_subcell(&self, cell : Cell, index : usize) -> Cell {
-		if !index_in_len(index, self.count_subcells()) {
-			panic!("Cell::with_subcell(): index not possible!");
-		}
-		match &self {
-			&&Cell::Complex { cells : ref cells_out } => {
-				let mut c : Vec<Cell> = cells_out.clone();
-				c[index] = cell;
-				return Cell::Complex { cells : c };
-			},
-			&&Cell::Scope { id : id_out, .. } => {
-				return Cell::Scope { id : id_out, body : Box::new(cell) };
-			},
-			&&Cell::Case { condition : ref cond_out, conclusion : ref conc_out } => {
-				if index == 0 {
-					return Cell::Case { condition : Box::new(cell), conclusion : conc_out.clone() };
-				} else if index == 1 {
-					return Cell::Case { condition : cond_out.clone() , conclusion : Box::new(cell) };
-				} else {
-					panic!("Cell::with_subcell(): Case out of range");
-				}
-			},
-			_ => panic!("Cell::with_subcell(): unacceptable!")
+		match self.try_with_subcell(cell, index) {
+			Ok(x) => x,
+			Err(srl_error) => panic!("{}", srl_error)
 		}
 	}
 
-	pub fn recurse<T>(&self, mut t : T, lambda_expr : fn(&Cell, T) -> T) -> T {
-		t = lambda_expr(&self, t);
-		for index in 0..self.count_subcells() {
-			t = self.get_subcell(index).recurse(t, lambda_expr);
+	// pre-order, see visit.rs for paths and early exit
+	pub fn recurse<T>(&self, mut t : T, mut lambda_expr : impl FnMut(&Cell, T) -> T) -> T {
+		for (cell, _) in self.pre_order() {
+			t = lambda_expr(cell, t);
 		}
 		t
 	}
@@ -258,6 +294,9 @@ impl Cell {
 			},
 			&Cell::Complex { cells : ref cells_out } => {
 				if let &Cell::Complex { cells : ref cells2_out } = cell {
+					if cells_out.len() != cells2_out.len() {
+						return false;
+					}
 					for i in 0..cells_out.len() {
 						if cells_out[i] != cells2_out[i] {
 							return false;
@@ -307,3 +346,30 @@ fn test_to_string() {
 	assert_eq!(&complex(vec![simple_by_str("a"), simple_by_str("b")]).to_string(), "(a b)");
 	assert_eq!(&scope(3, simple_by_str("b")).to_string(), "{3 b}");
 }
+
+#[test]
+fn test_replace_subcell() {
+	use gen::*;
+	let mut cell = complex(vec![simple_by_str("a"), scope(0, complex(vec![simple_by_str("p"), var(0)]))]);
+	let old = cell.replace_subcell(&[1, 0, 1], simple_by_str("x")).unwrap();
+	assert_eq!(old, var(0));
+	assert_eq!(cell.to_string(), "(a {0 (p x)})");
+
+	assert!(cell.replace_subcell(&[1, 0, 2], simple_by_str("x")).is_err());
+	assert!(cell.replace_subcell(&[0], Cell::Complex { cells : vec![simple_by_str("x")] }).is_err());
+	assert_eq!(cell.to_string(), "(a {0 (p x)})");
+
+	cell.edit_subcell(&[1], |x| *x = simple_by_str("b")).unwrap();
+	assert_eq!(cell.to_string(), "(a b)");
+	assert!(cell.edit_subcell(&[2], |_| {}).is_err());
+}
+
+#[test]
+fn test_edit_subcell_keeps_invariant() {
+	use gen::*;
+	let mut cell = complex(vec![simple_by_str("a"), complex(vec![simple_by_str("p"), simple_by_str("q")])]);
+	assert!(cell.edit_subcell(&[1], |x| if let Cell::Complex { ref mut cells } = *x { cells.pop(); }).is_err());
+	assert_eq!(cell.to_string(), "(a (p q))");
+	assert!(cell.edit_subcell(&[], |x| *x = Cell::Complex { cells : vec![] }).is_err());
+	assert_eq!(cell.to_string(), "(a (p q))");
+}
@@ -0,0 +1,167 @@
+use std::mem;
+use cell::Cell;
+use error::{SRLError, NavigationErrorKind, CellErrorKind};
+use navi::{CellID, CellPath};
+use gen::*;
+use misc::has_short_complex;
+
+// zipper over a cell: the focused subcell is owned by the cursor,
+// every parent on the way up is stored with a hole at the index we came from.
+// all moves are O(1), only the conversions back into a path are O(depth).
+pub struct CellCursor {
+	focus : Cell,
+	crumbs : Vec<Crumb>
+}
+
+struct Crumb {
+	parent : Cell, // contains a placeholder at index
+	index : usize
+}
+
+fn placeholder() -> Cell {
+	var(0)
+}
+
+impl CellCursor {
+	pub fn new(root_cell : Cell) -> CellCursor {
+		CellCursor { focus : root_cell, crumbs : Vec::new() }
+	}
+
+	pub fn from_path(path : CellPath) -> CellCursor {
+		let indices = path.get_indices();
+		let mut cursor = CellCursor::new(path.get_root_cell());
+		for index in indices {
+			// CellPath guarantees valid indices
+			if let Err(srl_error) = cursor.down(index) {
+				panic!("CellCursor::from_path: invalid path: {:?}", srl_error);
+			}
+		}
+		cursor
+	}
+
+	pub fn get_focus(&self) -> &Cell { &self.focus }
+	pub fn depth(&self) -> usize { self.crumbs.len() }
+	pub fn is_root(&self) -> bool { self.crumbs.is_empty() }
+
+	// the index of the focus in its parent
+	pub fn get_index(&self) -> Option<usize> {
+		self.crumbs.last().map(|x| x.index)
+	}
+
+	pub fn get_indices(&self) -> Vec<usize> {
+		self.crumbs.iter().map(|x| x.index).collect()
+	}
+
+	pub fn down(&mut self, index : usize) -> Result<(), SRLError> {
+		let child = match self.focus.get_subcell_mut(index) {
+			Some(x) => mem::replace(x, placeholder()),
+			None => return Err(SRLError::navigation("CellCursor::down", NavigationErrorKind::InvalidIndex))
+		};
+		let parent = mem::replace(&mut self.focus, child);
+		self.crumbs.push(Crumb { parent : parent, index : index });
+		Ok(())
+	}
+
+	pub fn up(&mut self) -> Result<(), SRLError> {
+		let Crumb { mut parent, index } = match self.crumbs.pop() {
+			Some(x) => x,
+			None => return Err(SRLError::navigation("CellCursor::up", NavigationErrorKind::NoParent))
+		};
+		match parent.get_subcell_mut(index) {
+			Some(x) => mem::swap(x, &mut self.focus),
+			None => panic!("CellCursor::up: hole vanished - should not happen")
+		}
+		self.focus = parent;
+		Ok(())
+	}
+
+	pub fn left(&mut self) -> Result<(), SRLError> {
+		match self.get_index() {
+			None => Err(SRLError::navigation("CellCursor::left", NavigationErrorKind::NoParent)),
+			Some(0) => Err(SRLError::navigation("CellCursor::left", NavigationErrorKind::NoLeftSibling)),
+			Some(index) => {
+				self.up()?;
+				self.down(index - 1)
+			}
+		}
+	}
+
+	pub fn right(&mut self) -> Result<(), SRLError> {
+		let index = match self.crumbs.last() {
+			None => return Err(SRLError::navigation("CellCursor::right", NavigationErrorKind::NoParent)),
+			Some(crumb) => {
+				if crumb.index + 1 >= crumb.parent.count_subcells() {
+					return Err(SRLError::navigation("CellCursor::right", NavigationErrorKind::NoRightSibling));
+				}
+				crumb.index
+			}
+		};
+		self.up()?;
+		self.down(index + 1)
+	}
+
+	pub fn top(&mut self) {
+		while let Ok(_) = self.up() {}
+	}
+
+	// replaces the focused cell and returns the old one
+	pub fn replace(&mut self, cell : Cell) -> Result<Cell, SRLError> {
+		if has_short_complex(&cell) {
+			return Err(SRLError::invalid_cell("CellCursor::replace", CellErrorKind::ShortComplex));
+		}
+		Ok(mem::replace(&mut self.focus, cell))
+	}
+
+	pub fn into_root_cell(mut self) -> Cell {
+		self.top();
+		self.focus
+	}
+
+	pub fn into_path(self) -> CellPath {
+		let indices = self.get_indices();
+		let root = self.into_root_cell();
+		match CellPath::create(root, indices) {
+			Ok(x) => x,
+			Err(srl_error) => panic!("CellCursor::into_path: {:?}", srl_error)
+		}
+	}
+
+	pub fn get_cell_id(&self, rule_id : usize) -> CellID {
+		CellID::create(rule_id, self.get_indices())
+	}
+}
+
+#[test]
+fn test_cell_cursor() {
+	// (a {0 (p 0)} b)
+	let cell = complex(vec![simple_by_str("a"), scope(0, complex(vec![simple_by_str("p"), var(0)])), simple_by_str("b")]);
+	let mut cursor = CellCursor::new(cell.clone());
+
+	assert!(cursor.up().is_err());
+	assert!(cursor.right().is_err());
+	cursor.down(0).unwrap();
+	assert_eq!(cursor.get_focus(), &simple_by_str("a"));
+	assert!(cursor.left().is_err());
+	cursor.right().unwrap();
+	cursor.down(0).unwrap();
+	cursor.down(1).unwrap();
+	assert_eq!(cursor.get_focus(), &var(0));
+	assert_eq!(cursor.get_indices(), vec![1, 0, 1]);
+	assert!(cursor.right().is_err());
+	cursor.left().unwrap();
+	assert_eq!(cursor.get_focus(), &simple_by_str("p"));
+	assert!(cursor.down(0).is_err());
+
+	assert_eq!(cursor.replace(simple_by_str("q")).unwrap(), simple_by_str("p"));
+	assert!(cursor.replace(Cell::Complex { cells : vec![] }).is_err());
+
+	let path = cursor.into_path();
+	assert_eq!(path.get_indices(), vec![1, 0, 0]);
+	assert_eq!(path.get_cell(), simple_by_str("q"));
+	assert_eq!(path.get_root_cell().to_string(), "(a {0 (q 0)} b)");
+
+	let mut cursor = CellCursor::from_path(CellPath::create(cell.clone(), vec![2]).unwrap());
+	assert_eq!(cursor.get_focus(), &simple_by_str("b"));
+	cursor.top();
+	assert_eq!(cursor.get_focus(), &cell);
+}
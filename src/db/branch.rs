@@ -0,0 +1,111 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use error::{SRLError, DatabaseErrorKind};
+use navi::RuleHandle;
+use super::Database;
+
+// a branch is a clone of a database: forking only copies pointers, the rules and the index entries are shared,
+// a branch copies the parts it changes on its first write to them.
+// all branches draw their handles from the same counter, so in every branch a handle names the same rule.
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct BranchDiff {
+	only_left : Vec<RuleHandle>,
+	only_right : Vec<RuleHandle>
+}
+
+impl BranchDiff {
+	pub fn get_only_left(&self) -> &[RuleHandle] { &self.only_left }
+	pub fn get_only_right(&self) -> &[RuleHandle] { &self.only_right }
+
+	pub fn is_empty(&self) -> bool {
+		self.only_left.is_empty() && self.only_right.is_empty()
+	}
+}
+
+impl Database {
+	// O(1), source rules stay write protected in the fork
+	pub fn fork(&self) -> Database {
+		self.clone()
+	}
+
+	pub fn is_branch_of(&self, other : &Database) -> bool {
+		Arc::ptr_eq(&self.next_handle, &other.next_handle)
+	}
+
+	fn check_branch(&self, other : &Database, procedure : &str) -> Result<(), SRLError> {
+		if !self.is_branch_of(other) {
+			return Err(SRLError::database(procedure, DatabaseErrorKind::UnrelatedBranches));
+		}
+		Ok(())
+	}
+
+	// the rules (by handle), that only one of the branches contains
+	pub fn compare(&self, other : &Database) -> Result<BranchDiff, SRLError> {
+		self.check_branch(other, "Database::compare")?;
+		let mut diff = BranchDiff { only_left : Vec::new(), only_right : Vec::new() };
+		let (mut i, mut j) = (0, 0);
+		while i < self.handles.len() || j < other.handles.len() {
+			match (self.handles.get(i), other.handles.get(j)) {
+				(Some(x), Some(y)) if x == y => { i += 1; j += 1; },
+				(Some(x), Some(y)) if x < y => { diff.only_left.push(*x); i += 1; },
+				(Some(x), None) => { diff.only_left.push(*x); i += 1; },
+				(_, Some(y)) => { diff.only_right.push(*y); j += 1; },
+				(None, None) => unreachable!()
+			}
+		}
+		Ok(diff)
+	}
+
+	// adds the rules of other, that self does not contain, together with their proof steps; returns how many rules were added
+	// -- a rule, that equals a rule of self (e.g. derived in both branches), is not added twice
+	// -- a rule, that was deleted in self after forking, is added again if other still contains it
+	// -- the merged rules are placed by handle, so the CellIDs of the proof steps get the new rule positions (like in prune)
+	pub fn merge(&mut self, other : &Database) -> Result<usize, SRLError> {
+		self.check_branch(other, "Database::merge")?;
+		let diff = self.compare(other)?;
+
+		let mut map : HashMap<RuleHandle, RuleHandle> = HashMap::new(); // handles of duplicates in other -> handles in self
+		let mut added = 0;
+		for handle in diff.only_right {
+			let rule = other.rules[other.get_rule_index(handle)?].clone();
+			match self.rules.iter().position(|x| *x == rule) {
+				Some(index) => { map.insert(handle, self.handles[index]); },
+				None => {
+					self.insert_rule_with_handle(rule.clone(), handle);
+					self.check_new_rule(handle, &rule);
+					added += 1;
+				}
+			}
+		}
+
+		let logged : HashSet<RuleHandle> = self.proof_log.iter().filter_map(|x| x.get_result().get_handle()).collect();
+		for step in other.proof_log.iter() {
+			let result = step.get_result().get_handle();
+			if result.map_or(true, |x| logged.contains(&x) || map.contains_key(&x)) {
+				continue;
+			}
+			Arc::make_mut(&mut self.proof_log).push(step.with_mapped_handles(&map));
+		}
+		let proof_log = self.proof_log.iter().map(|x| self.with_current_positions(x)).collect();
+		self.proof_log = Arc::new(proof_log);
+		self.redo_steps.clear();
+		Ok(added)
+	}
+}
+
+#[test]
+fn test_fork_shares_rules() {
+	use navi::CellID;
+
+	let db = Database::by_string("{0 wow}. = 'false' (= 'true' x).").unwrap();
+	let mut fork = db.fork();
+	assert!(Arc::ptr_eq(&db.rules, &fork.rules));
+
+	fork.add_eqt(CellID::create(1, vec![0])).unwrap();
+	assert!(!Arc::ptr_eq(&db.rules, &fork.rules));
+	for i in 0..db.count_rules() {
+		assert!(Arc::ptr_eq(&db.rules[i], &fork.rules[i]));
+	}
+	assert!(Arc::ptr_eq(&fork.rules[3], &fork.proof_log[0].get_shared_rule()));
+}
@@ -0,0 +1,123 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use cell::Cell;
+use error::SRLError;
+use navi::RuleHandle;
+use super::Database;
+use super::proof::LawApplication;
+
+// all rules of a database are normalized (see Database::by_string and add_rule),
+// so alpha-equivalent rules are equal cells and the rules themselves are the keys
+
+impl Database {
+	// in dedup mode, a law that derives an existing rule does not add it again (and does not log it),
+	// use derive to learn the index of the existing rule
+	pub fn set_dedup(&mut self, dedup : bool) {
+		if !dedup {
+			self.normal_forms = None;
+			return;
+		}
+		if self.normal_forms.is_some() {
+			return;
+		}
+		let mut normal_forms = HashMap::new();
+		for (rule, handle) in self.rules.iter().zip(self.handles.iter()) {
+			normal_forms.entry(rule.clone()).or_insert(*handle);
+		}
+		self.normal_forms = Some(Arc::new(normal_forms));
+	}
+
+	pub fn is_dedup(&self) -> bool {
+		self.normal_forms.is_some()
+	}
+
+	pub(super) fn add_normal_form(&mut self, handle : RuleHandle, rule : &Arc<Cell>) {
+		if let Some(ref mut normal_forms) = self.normal_forms {
+			Arc::make_mut(normal_forms).entry(rule.clone()).or_insert(handle);
+		}
+	}
+
+	// called after rule has been removed, another copy of it (if there is one) takes over
+	pub(super) fn forget_normal_form(&mut self, handle : RuleHandle, rule : &Arc<Cell>) {
+		let other = match self.rules.iter().position(|x| x == rule) {
+			Some(x) => Some(self.handles[x]),
+			None => None
+		};
+		if let Some(ref mut normal_forms) = self.normal_forms {
+			if normal_forms.get(rule) == Some(&handle) {
+				let normal_forms = Arc::make_mut(normal_forms);
+				match other {
+					Some(x) => { normal_forms.insert(rule.clone(), x); },
+					None => { normal_forms.remove(rule); }
+				}
+			}
+		}
+	}
+
+	// the index of the first rule, that is alpha-equivalent to rule
+	pub fn find_rule(&self, rule : &Cell) -> Option<usize> {
+		let norm = match rule.get_normalized() {
+			Ok(x) => x,
+			Err(_) => return None
+		};
+		match self.normal_forms {
+			Some(ref normal_forms) => normal_forms.get(&norm).and_then(|x| self.get_rule_index(*x).ok()),
+			None => self.rules.iter().position(|x| **x == norm)
+		}
+	}
+
+	// applies the law and returns the index of the resulting rule, in dedup mode this may be an older rule
+	pub fn derive(&mut self, application : &LawApplication) -> Result<usize, SRLError> {
+		let rule = application.apply(self)?;
+		if self.is_dedup() {
+			if let Some(index) = self.find_rule(&rule) {
+				return Ok(index);
+			}
+		}
+		Ok(self.count_rules() - 1)
+	}
+
+	// deletes every derived rule, that is alpha-equivalent to another rule, and returns how many were deleted.
+	// of alpha-equivalent rules the one without proof step (a source rule, or a loaded derived rule) is kept,
+	// otherwise the one derived first; proof steps referring to a deleted rule now refer to the kept one,
+	// and like in prune the CellIDs of the proof steps get the new rule positions
+	pub fn dedup(&mut self) -> Result<usize, SRLError> {
+		let mut step_positions : HashMap<RuleHandle, usize> = HashMap::new();
+		for (i, step) in self.proof_log.iter().enumerate() {
+			if let Some(handle) = step.get_result().get_handle() {
+				step_positions.entry(handle).or_insert(i);
+			}
+		}
+
+		// rule -> (step position or None, handle) of the rule to keep
+		let mut kept : HashMap<Arc<Cell>, (Option<usize>, RuleHandle)> = HashMap::new();
+		for (rule, handle) in self.rules.iter().zip(self.handles.iter()) {
+			let candidate = (step_positions.get(handle).cloned(), *handle);
+			let entry = kept.entry(rule.clone()).or_insert(candidate);
+			if candidate < *entry {
+				*entry = candidate; // None is smaller than every Some
+			}
+		}
+
+		let mut map : HashMap<RuleHandle, RuleHandle> = HashMap::new();
+		for index in (self.src_rules_count..self.count_rules()).rev() {
+			let handle = self.handles[index];
+			let kept_handle = kept[&self.rules[index]].1;
+			if kept_handle != handle {
+				map.insert(handle, kept_handle);
+				self.delete_rule(index)?;
+			}
+		}
+
+		if !map.is_empty() {
+			let removed : HashSet<&RuleHandle> = map.keys().collect();
+			let proof_log = self.proof_log.iter()
+				.filter(|x| x.get_result().get_handle().map_or(true, |h| !removed.contains(&h)))
+				.map(|x| self.with_current_positions(&x.with_mapped_handles(&map)))
+				.collect();
+			self.proof_log = Arc::new(proof_log);
+			self.redo_steps.clear();
+		}
+		Ok(map.len())
+	}
+}
@@ -0,0 +1,193 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use cell::Cell;
+use navi::RuleHandle;
+
+// discrimination tree over all subcells of all rules, except the Vars themselves (they would match every query)
+// every cell is flattened in pre-order into keys, Vars (of the indexed cells and of the query) are wildcards
+// the retrieved cells are candidates: scope ids and var bindings are not checked
+
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+enum Key {
+	Simple(String),
+	Complex(usize),
+	Scope,
+	Case,
+	Var
+}
+
+impl Key {
+	fn arity(&self) -> usize {
+		match self {
+			&Key::Complex(x) => x,
+			&Key::Scope => 1,
+			&Key::Case => 2,
+			&Key::Simple(_) | &Key::Var => 0
+		}
+	}
+}
+
+#[derive(Clone)]
+struct Node {
+	children : HashMap<Key, usize>,
+	entries : Vec<(RuleHandle, Vec<usize>)>
+}
+
+impl Node {
+	fn new() -> Node {
+		Node { children : HashMap::new(), entries : Vec::new() }
+	}
+}
+
+// the nodes are shared between clones of the tree
+#[derive(Clone)]
+pub struct DiscriminationTree {
+	nodes : Vec<Arc<Node>> // nodes[0] is the root
+}
+
+fn keys_r(cell : &Cell, keys : &mut Vec<Key>) {
+	match cell {
+		&Cell::Simple { ref string } => keys.push(Key::Simple(string.get_string())),
+		&Cell::Complex { ref cells } => {
+			keys.push(Key::Complex(cells.len()));
+			for x in cells {
+				keys_r(x, keys);
+			}
+		},
+		&Cell::Scope { ref body, .. } => {
+			keys.push(Key::Scope);
+			keys_r(body, keys);
+		},
+		&Cell::Case { ref condition, ref conclusion } => {
+			keys.push(Key::Case);
+			keys_r(condition, keys);
+			keys_r(conclusion, keys);
+		},
+		&Cell::Var {..} => keys.push(Key::Var)
+	}
+}
+
+fn keys(cell : &Cell) -> Vec<Key> {
+	let mut keys = Vec::new();
+	keys_r(cell, &mut keys);
+	keys
+}
+
+// the position behind the subcell starting at keys[start]
+fn skip(keys : &[Key], start : usize) -> usize {
+	let mut open = 1;
+	let mut i = start;
+	while open > 0 {
+		open = open - 1 + keys[i].arity();
+		i += 1;
+	}
+	i
+}
+
+pub fn is_indexed(cell : &Cell) -> bool {
+	match cell {
+		&Cell::Var {..} => false,
+		_ => true
+	}
+}
+
+// whether the tree would return the (indexed) cell as candidate for term
+pub fn is_candidate(term : &Cell, cell : &Cell) -> bool {
+	match (term, cell) {
+		(&Cell::Var {..}, _) | (_, &Cell::Var {..}) => true,
+		(&Cell::Simple { string : ref s1 }, &Cell::Simple { string : ref s2 }) => s1 == s2,
+		(&Cell::Complex { cells : ref c1 }, &Cell::Complex { cells : ref c2 }) => {
+			c1.len() == c2.len() && c1.iter().zip(c2.iter()).all(|(x, y)| is_candidate(x, y))
+		},
+		(&Cell::Scope { body : ref b1, .. }, &Cell::Scope { body : ref b2, .. }) => is_candidate(b1, b2),
+		(&Cell::Case { condition : ref cond1, conclusion : ref conc1 }, &Cell::Case { condition : ref cond2, conclusion : ref conc2 }) => {
+			is_candidate(cond1, cond2) && is_candidate(conc1, conc2)
+		},
+		_ => false
+	}
+}
+
+impl DiscriminationTree {
+	pub fn new() -> DiscriminationTree {
+		DiscriminationTree { nodes : vec![Arc::new(Node::new())] }
+	}
+
+	pub fn add_rule(&mut self, handle : RuleHandle, rule : &Cell) {
+		for (cell, indices) in rule.pre_order().filter(|x| is_indexed(x.0)) {
+			let mut node = 0;
+			for key in keys(cell) {
+				node = match self.nodes[node].children.get(&key) {
+					Some(&x) => x,
+					None => {
+						self.nodes.push(Arc::new(Node::new()));
+						let new = self.nodes.len() - 1;
+						Arc::make_mut(&mut self.nodes[node]).children.insert(key, new);
+						new
+					}
+				};
+			}
+			Arc::make_mut(&mut self.nodes[node]).entries.push((handle, indices));
+		}
+	}
+
+	// empty nodes are kept, they are reused by later rules of the same shape
+	pub fn delete_rule(&mut self, handle : RuleHandle, rule : &Cell) {
+		for (cell, _) in rule.pre_order().filter(|x| is_indexed(x.0)) {
+			let mut node = 0;
+			for key in keys(cell) {
+				node = match self.nodes[node].children.get(&key) {
+					Some(&x) => x,
+					None => panic!("DiscriminationTree::delete_rule: rule was not indexed - should not happen")
+				};
+			}
+			Arc::make_mut(&mut self.nodes[node]).entries.retain(|x| x.0 != handle);
+		}
+	}
+
+	// (rule handle, indices) of every subcell, that may match term
+	pub fn get_candidates(&self, term : &Cell) -> Vec<(RuleHandle, Vec<usize>)> {
+		let mut leaves = Vec::new();
+		self.retrieve_r(0, &keys(term), 0, &mut leaves);
+		leaves.sort();
+		leaves.dedup();
+
+		let mut result = Vec::new();
+		for leaf in leaves {
+			result.extend(self.nodes[leaf].entries.iter().cloned());
+		}
+		result.sort();
+		result
+	}
+
+	fn retrieve_r(&self, node : usize, keys : &[Key], position : usize, leaves : &mut Vec<usize>) {
+		if position == keys.len() {
+			leaves.push(node);
+			return;
+		}
+		if keys[position] == Key::Var {
+			let mut ends = Vec::new();
+			self.skip_r(node, 1, &mut ends);
+			for end in ends {
+				self.retrieve_r(end, keys, position + 1, leaves);
+			}
+			return;
+		}
+		if let Some(&child) = self.nodes[node].children.get(&keys[position]) {
+			self.retrieve_r(child, keys, position + 1, leaves);
+		}
+		if let Some(&child) = self.nodes[node].children.get(&Key::Var) {
+			self.retrieve_r(child, keys, skip(keys, position), leaves);
+		}
+	}
+
+	// collects the nodes reachable from node by reading open complete subcells
+	fn skip_r(&self, node : usize, open : usize, ends : &mut Vec<usize>) {
+		if open == 0 {
+			ends.push(node);
+			return;
+		}
+		for (key, &child) in &self.nodes[node].children {
+			self.skip_r(child, open - 1 + key.arity(), ends);
+		}
+	}
+}
@@ -0,0 +1,137 @@
+use std::sync::Arc;
+use std::mem;
+use cell::Cell;
+use error::{SRLError, DatabaseErrorKind};
+use gen::{true_cell, false_cell, equals_cell};
+use navi::RuleHandle;
+use super::Database;
+use super::proof::ProofStep;
+
+// goals are cells, that are to be derived. every new rule is compared to the open goals,
+// a goal is proved as soon as an alpha-equivalent rule exists
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct Goal {
+	cell : Cell, // normalized
+	proof : Option<RuleHandle> // the rule, that proved the goal
+}
+
+impl Goal {
+	pub fn get_cell(&self) -> &Cell { &self.cell }
+	pub fn is_proved(&self) -> bool { self.proof.is_some() }
+	pub fn get_proof(&self) -> Option<RuleHandle> { self.proof }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub enum Event {
+	GoalProved { goal : usize, rule : RuleHandle },
+	GoalReopened { goal : usize }, // the rule proving the goal is gone (deleted, undone or rolled back)
+	Inconsistency { rule : RuleHandle } // 'false' or (= 'true' 'false') has become a rule
+}
+
+fn is_contradiction(rule : &Cell) -> bool {
+	*rule == false_cell()
+		|| *rule == equals_cell(true_cell(), false_cell())
+		|| *rule == equals_cell(false_cell(), true_cell())
+}
+
+impl Database {
+	// returns the index of the goal; a goal, that already is a rule, is proved at once
+	pub fn add_goal(&mut self, cell : Cell) -> Result<usize, SRLError> {
+		let cell = cell.get_normalized()?;
+		let index = self.goals.len();
+		Arc::make_mut(&mut self.goals).push(Goal { cell : cell.clone(), proof : None });
+		if let Some(rule_index) = self.find_rule(&cell) {
+			let handle = self.handles[rule_index];
+			self.prove_goal(index, handle);
+		}
+		Ok(index)
+	}
+
+	pub fn get_goals(&self) -> &[Goal] {
+		&self.goals
+	}
+
+	pub fn get_open_goals(&self) -> Vec<usize> {
+		(0..self.goals.len()).filter(|x| !self.goals[*x].is_proved()).collect()
+	}
+
+	// the law applications of the proof log, that lead to the rule proving the goal, in order.
+	// empty for open goals and for goals proved by rules without proof step
+	pub fn get_goal_steps(&self, goal : usize) -> Result<Vec<ProofStep>, SRLError> {
+		let handle = match self.goals.get(goal) {
+			Some(x) => match x.get_proof() {
+				Some(handle) => handle,
+				None => return Ok(Vec::new())
+			},
+			None => return Err(SRLError::database("Database::get_goal_steps", DatabaseErrorKind::GoalOutOfRange))
+		};
+		let mut needed = self.get_dependency_graph().get_dependencies(handle);
+		needed.push(handle);
+		let mut steps = Vec::new();
+		for step in self.proof_log.iter() {
+			if let Some(result) = step.get_result().get_handle() {
+				if needed.contains(&result) && !steps.iter().any(|x : &ProofStep| x.get_result().get_handle() == Some(result)) {
+					steps.push(step.clone());
+				}
+			}
+		}
+		Ok(steps)
+	}
+
+	// returns the events since the last call, oldest first
+	pub fn take_events(&mut self) -> Vec<Event> {
+		mem::replace(&mut self.events, Vec::new())
+	}
+
+	// whether 'false' or (= 'true' 'false') is a rule
+	pub fn is_inconsistent(&self) -> bool {
+		self.rules.iter().any(|x| is_contradiction(x))
+	}
+
+	fn prove_goal(&mut self, goal : usize, handle : RuleHandle) {
+		Arc::make_mut(&mut self.goals)[goal].proof = Some(handle);
+		self.events.push(Event::GoalProved { goal : goal, rule : handle });
+	}
+
+	// called for every rule added by a law application (or by redo or merge)
+	pub(super) fn check_new_rule(&mut self, handle : RuleHandle, rule : &Cell) {
+		for goal in self.get_open_goals() {
+			if self.goals[goal].cell == *rule {
+				self.prove_goal(goal, handle);
+			}
+		}
+		if is_contradiction(rule) {
+			self.events.push(Event::Inconsistency { rule : handle });
+		}
+	}
+
+	// reopens the goals proved by a rule, that no longer exists, unless another copy of that rule takes over
+	pub(super) fn check_goal_proofs(&mut self) {
+		for goal in 0..self.goals.len() {
+			let handle = match self.goals[goal].get_proof() {
+				Some(x) => x,
+				None => continue
+			};
+			if self.get_rule_index(handle).is_ok() {
+				continue;
+			}
+			let proof = self.find_rule(&self.goals[goal].cell).map(|x| self.handles[x]);
+			Arc::make_mut(&mut self.goals)[goal].proof = proof;
+			if proof.is_none() {
+				self.events.push(Event::GoalReopened { goal : goal });
+			}
+		}
+	}
+
+	// called by rollback with the goals as they were before, reports the goals, that are open or proved again
+	pub(super) fn check_restored_goals(&mut self, goals : &[Goal]) {
+		for (goal, (restored, old)) in self.goals.iter().zip(goals.iter()).enumerate() {
+			match (old.get_proof(), restored.get_proof()) {
+				(Some(_), None) => self.events.push(Event::GoalReopened { goal : goal }),
+				(None, Some(handle)) => self.events.push(Event::GoalProved { goal : goal, rule : handle }),
+				_ => {}
+			}
+		}
+	}
+}
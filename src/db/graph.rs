@@ -0,0 +1,172 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write;
+use cell::Cell;
+use error::SRLError;
+use navi::RuleHandle;
+use super::Database;
+use super::reason::Law;
+
+// which rule was derived from which, according to the proof log.
+// rules deleted since their derivation stay in the graph (without index), so chains through them are not cut.
+
+struct Node {
+	index : Option<usize>, // position in the database, None if deleted
+	rule : Cell,
+	law : Option<Law>, // None for axioms, i.e. rules without proof step
+	inputs : BTreeSet<RuleHandle>,
+	dependents : BTreeSet<RuleHandle>
+}
+
+pub struct DependencyGraph {
+	nodes : BTreeMap<RuleHandle, Node>
+}
+
+fn escape(string : &str) -> String {
+	let mut result = String::new();
+	for c in string.chars() {
+		match c {
+			'"' => result.push_str("\\\""),
+			'\\' => result.push_str("\\\\"),
+			'\n' => result.push_str("\\n"),
+			_ => result.push(c)
+		}
+	}
+	result
+}
+
+impl DependencyGraph {
+	pub fn contains(&self, handle : RuleHandle) -> bool {
+		self.nodes.contains_key(&handle)
+	}
+
+	pub fn get_handles(&self) -> Vec<RuleHandle> {
+		self.nodes.keys().cloned().collect()
+	}
+
+	pub fn get_index(&self, handle : RuleHandle) -> Option<usize> {
+		self.nodes.get(&handle).and_then(|x| x.index)
+	}
+
+	pub fn is_axiom(&self, handle : RuleHandle) -> bool {
+		self.nodes.get(&handle).map_or(false, |x| x.law.is_none())
+	}
+
+	// the rules handle was directly derived from
+	pub fn get_inputs(&self, handle : RuleHandle) -> Vec<RuleHandle> {
+		self.nodes.get(&handle).map_or(Vec::new(), |x| x.inputs.iter().cloned().collect())
+	}
+
+	// every rule, that handle was derived from (directly or not), sorted
+	pub fn get_dependencies(&self, handle : RuleHandle) -> Vec<RuleHandle> {
+		self.reachable(handle, |x| &x.inputs)
+	}
+
+	// every rule derived from handle (directly or not), sorted
+	pub fn get_dependents(&self, handle : RuleHandle) -> Vec<RuleHandle> {
+		self.reachable(handle, |x| &x.dependents)
+	}
+
+	// the axioms, that handle ultimately rests on
+	pub fn get_axioms(&self, handle : RuleHandle) -> Vec<RuleHandle> {
+		self.get_dependencies(handle).into_iter().filter(|x| self.is_axiom(*x)).collect()
+	}
+
+	fn reachable(&self, handle : RuleHandle, edges : impl Fn(&Node) -> &BTreeSet<RuleHandle>) -> Vec<RuleHandle> {
+		let mut found = BTreeSet::new();
+		let mut todo = vec![handle];
+		while let Some(current) = todo.pop() {
+			if let Some(node) = self.nodes.get(&current) {
+				for next in edges(node) {
+					if found.insert(*next) {
+						todo.push(*next);
+					}
+				}
+			}
+		}
+		found.into_iter().collect()
+	}
+
+	fn get_name(&self, handle : RuleHandle) -> String {
+		match self.get_index(handle) {
+			Some(index) => format!("#{}@{}", index, handle.get_id()),
+			None => format!("@{} (deleted)", handle.get_id())
+		}
+	}
+
+	// edges point from a derived rule to the rules it was derived from, axioms are boxes
+	pub fn to_dot(&self) -> String {
+		let mut string = String::from("digraph dependencies {\n");
+		for (handle, node) in &self.nodes {
+			let label = format!("{} {}", self.get_name(*handle), node.rule.to_rule_string());
+			let shape = if node.law.is_none() { ", shape=box" } else { "" };
+			writeln!(string, "\tr{} [label=\"{}\"{}];", handle.get_id(), escape(&label), shape).unwrap();
+		}
+		for (handle, node) in &self.nodes {
+			if let Some(law) = node.law {
+				for input in &node.inputs {
+					writeln!(string, "\tr{} -> r{} [label=\"{}\"];", handle.get_id(), input.get_id(), law.get_name()).unwrap();
+				}
+			}
+		}
+		string.push_str("}\n");
+		string
+	}
+
+	// {"nodes":[{"handle":3,"index":3,"rule":"...","law":"add_eqt","inputs":[1]}, ...]}, index is null for deleted rules, law is null for axioms
+	pub fn to_json(&self) -> String {
+		let mut nodes = Vec::new();
+		for (handle, node) in &self.nodes {
+			let index = node.index.map_or("null".to_string(), |x| x.to_string());
+			let law = node.law.map_or("null".to_string(), |x| format!("\"{}\"", x.get_name()));
+			let inputs : Vec<String> = node.inputs.iter().map(|x| x.get_id().to_string()).collect();
+			nodes.push(format!("{{\"handle\":{},\"index\":{},\"rule\":\"{}\",\"law\":{},\"inputs\":[{}]}}",
+				handle.get_id(), index, escape(&node.rule.to_rule_string()), law, inputs.join(",")));
+		}
+		format!("{{\"nodes\":[{}]}}", nodes.join(","))
+	}
+}
+
+impl Database {
+	pub fn get_dependency_graph(&self) -> DependencyGraph {
+		let mut nodes = BTreeMap::new();
+		for (index, (rule, handle)) in self.rules.iter().zip(self.handles.iter()).enumerate() {
+			nodes.insert(*handle, Node { index : Some(index), rule : (**rule).clone(), law : None, inputs : BTreeSet::new(), dependents : BTreeSet::new() });
+		}
+		for step in self.proof_log.iter() {
+			let handle = match step.get_result().get_handle() {
+				Some(x) => x,
+				None => continue
+			};
+			let node = nodes.entry(handle).or_insert_with(|| Node { index : None, rule : step.get_rule(), law : None, inputs : BTreeSet::new(), dependents : BTreeSet::new() });
+			if node.law.is_some() {
+				continue; // the first step counts
+			}
+			node.law = Some(step.get_law());
+			node.inputs = step.get_inputs().iter().filter_map(|x| x.get_handle()).collect();
+		}
+
+		let edges : Vec<(RuleHandle, RuleHandle)> = nodes.iter()
+			.flat_map(|(handle, node)| node.inputs.iter().map(move |x| (*x, *handle)))
+			.collect();
+		for (input, dependent) in edges {
+			if let Some(node) = nodes.get_mut(&input) {
+				node.dependents.insert(dependent);
+			}
+		}
+		DependencyGraph { nodes : nodes }
+	}
+
+	// the current indices of all rules, that the rule at index was derived from
+	pub fn get_rule_dependencies(&self, index : usize) -> Result<Vec<usize>, SRLError> {
+		let graph = self.get_dependency_graph();
+		let handles = graph.get_dependencies(self.get_rule_handle(index)?);
+		Ok(handles.into_iter().filter_map(|x| graph.get_index(x)).collect())
+	}
+
+	// the current indices of all rules derived from the rule at index
+	pub fn get_rule_dependents(&self, index : usize) -> Result<Vec<usize>, SRLError> {
+		let graph = self.get_dependency_graph();
+		let handles = graph.get_dependents(self.get_rule_handle(index)?);
+		Ok(handles.into_iter().filter_map(|x| graph.get_index(x)).collect())
+	}
+}
@@ -0,0 +1,96 @@
+use std::mem;
+use std::sync::Arc;
+use error::{SRLError, DatabaseErrorKind};
+use super::Database;
+use super::proof::ProofStep;
+
+// undo/redo works on the law applications of the proof log,
+// transactions are snapshots of the whole database (including the proof log and the redo steps).
+// events are not part of a snapshot: a rollback keeps the events not taken yet and reports the goals,
+// that it reopens or proves again (an Inconsistency event is not retracted)
+
+impl Database {
+	pub fn can_undo(&self) -> bool { !self.proof_log.is_empty() }
+	pub fn can_redo(&self) -> bool { !self.redo_steps.is_empty() }
+
+	// removes the last law application from the proof log and its rule from the database (if it was not deleted already)
+	pub fn undo(&mut self) -> Option<ProofStep> {
+		let step = Arc::make_mut(&mut self.proof_log).pop()?;
+		if let Some(handle) = step.get_result().get_handle() {
+			if let Ok(index) = self.get_rule_index(handle) {
+				if let Err(srl_error) = self.remove_rule(index) {
+					panic!("Database::undo: {} - should not happen", srl_error);
+				}
+			}
+		}
+		self.redo_steps.push(step.clone());
+		Some(step)
+	}
+
+	// adds the rule of the last undone law application again, with its old handle
+	// -- applying a law clears the redo steps
+	pub fn redo(&mut self) -> Option<ProofStep> {
+		let step = self.redo_steps.pop()?;
+		let handle = match step.get_result().get_handle() {
+			Some(x) => x,
+			None => panic!("Database::redo: proof step without handle - should not happen")
+		};
+		self.insert_rule_with_handle(step.get_shared_rule(), handle);
+		Arc::make_mut(&mut self.proof_log).push(step.clone());
+		self.check_new_rule(handle, &step.get_shared_rule());
+		Some(step)
+	}
+
+	// transactions can be nested, commit and rollback refer to the innermost one
+	pub fn begin_transaction(&mut self) {
+		let transactions = mem::replace(&mut self.transactions, Vec::new());
+		let events = mem::replace(&mut self.events, Vec::new());
+		let snapshot = self.clone();
+		self.transactions = transactions;
+		self.events = events;
+		self.transactions.push(snapshot);
+	}
+
+	pub fn count_transactions(&self) -> usize {
+		self.transactions.len()
+	}
+
+	// keeps every change since begin_transaction
+	pub fn commit(&mut self) -> Result<(), SRLError> {
+		match self.transactions.pop() {
+			Some(_) => Ok(()),
+			None => Err(SRLError::database("Database::commit", DatabaseErrorKind::NoTransaction))
+		}
+	}
+
+	// restores the database as it was at begin_transaction
+	pub fn rollback(&mut self) -> Result<(), SRLError> {
+		let snapshot = match self.transactions.pop() {
+			Some(x) => x,
+			None => return Err(SRLError::database("Database::rollback", DatabaseErrorKind::NoTransaction))
+		};
+		let transactions = mem::replace(&mut self.transactions, Vec::new());
+		let events = mem::replace(&mut self.events, Vec::new());
+		let goals = self.goals.clone();
+		*self = snapshot;
+		self.transactions = transactions;
+		self.events = events;
+		self.check_restored_goals(&goals);
+		Ok(())
+	}
+
+	// runs f in a transaction, that is committed if f succeeds and rolled back otherwise
+	pub fn transaction<T>(&mut self, f : impl FnOnce(&mut Database) -> Result<T, SRLError>) -> Result<T, SRLError> {
+		self.begin_transaction();
+		match f(self) {
+			Ok(x) => {
+				self.commit()?;
+				Ok(x)
+			},
+			Err(srl_error) => {
+				self.rollback()?;
+				Err(srl_error)
+			}
+		}
+	}
+}
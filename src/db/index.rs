@@ -0,0 +1,129 @@
+use std::collections::{HashMap, BTreeMap};
+use std::sync::Arc;
+use cell::Cell;
+use navi::RuleHandle;
+
+// symbol -> where it occurs; keyed by RuleHandle, so deleting a rule does not shift the other entries
+// the entries are shared between clones of the index
+#[derive(Clone)]
+pub struct SymbolIndex {
+	entries : HashMap<String, Arc<SymbolEntry>>
+}
+
+#[derive(Clone)]
+struct SymbolEntry {
+	occurrences : BTreeMap<RuleHandle, Vec<Vec<usize>>>,
+	arities : BTreeMap<usize, usize> // arity -> count
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct SymbolStats {
+	occurrences : usize,
+	rules : usize,
+	arities : Vec<(usize, usize)>
+}
+
+impl SymbolStats {
+	pub fn get_occurrences(&self) -> usize { self.occurrences }
+	pub fn get_rules(&self) -> usize { self.rules }
+	// (arity, count), sorted by arity; a symbol has arity n if it heads a complex cell with n arguments
+	pub fn get_arities(&self) -> Vec<(usize, usize)> { self.arities.clone() }
+}
+
+// (symbol, indices, arity) of every simple cell in rule
+fn symbols_of(rule : &Cell) -> Vec<(String, Vec<usize>, Option<usize>)> {
+	let mut result = Vec::new();
+	for (cell, indices) in rule.pre_order() {
+		let string = match cell {
+			&Cell::Simple { ref string } => string.get_string(),
+			_ => continue
+		};
+		let arity = match indices.split_last() {
+			Some((&0, parent_indices)) => match rule.path_ref(parent_indices) {
+				Some(&Cell::Complex { ref cells }) => Some(cells.len() - 1),
+				_ => None
+			},
+			_ => None
+		};
+		result.push((string, indices, arity));
+	}
+	result
+}
+
+impl SymbolIndex {
+	pub fn new() -> SymbolIndex {
+		SymbolIndex { entries : HashMap::new() }
+	}
+
+	pub fn add_rule(&mut self, handle : RuleHandle, rule : &Cell) {
+		for (symbol, indices, arity) in symbols_of(rule) {
+			let entry = Arc::make_mut(self.entries.entry(symbol).or_insert_with(|| Arc::new(SymbolEntry { occurrences : BTreeMap::new(), arities : BTreeMap::new() })));
+			entry.occurrences.entry(handle).or_insert_with(Vec::new).push(indices);
+			if let Some(arity) = arity {
+				*entry.arities.entry(arity).or_insert(0) += 1;
+			}
+		}
+	}
+
+	pub fn delete_rule(&mut self, handle : RuleHandle, rule : &Cell) {
+		for (symbol, _, arity) in symbols_of(rule) {
+			let remove = match self.entries.get_mut(&symbol) {
+				Some(entry) => {
+					let entry = Arc::make_mut(entry);
+					entry.occurrences.remove(&handle);
+					if let Some(arity) = arity {
+						let count = entry.arities.remove(&arity).unwrap_or(0);
+						if count > 1 {
+							entry.arities.insert(arity, count - 1);
+						}
+					}
+					entry.occurrences.is_empty()
+				},
+				None => false
+			};
+			if remove {
+				self.entries.remove(&symbol);
+			}
+		}
+	}
+
+	pub fn contains(&self, symbol : &str) -> bool {
+		self.entries.contains_key(symbol)
+	}
+
+	pub fn count_symbols(&self) -> usize {
+		self.entries.len()
+	}
+
+	// sorted
+	pub fn get_symbols(&self) -> Vec<String> {
+		let mut symbols : Vec<String> = self.entries.keys().cloned().collect();
+		symbols.sort();
+		symbols
+	}
+
+	// (rule handle, indices) of every occurrence, sorted by handle and pre-order
+	pub fn get_occurrences(&self, symbol : &str) -> Vec<(RuleHandle, Vec<usize>)> {
+		let mut result = Vec::new();
+		if let Some(entry) = self.entries.get(symbol) {
+			for (handle, paths) in &entry.occurrences {
+				for indices in paths {
+					result.push((*handle, indices.clone()));
+				}
+			}
+		}
+		result
+	}
+
+	pub fn get_stats(&self, symbol : &str) -> Option<SymbolStats> {
+		let entry = match self.entries.get(symbol) {
+			Some(x) => x,
+			None => return None
+		};
+		Some(SymbolStats {
+			occurrences : entry.occurrences.values().map(|x| x.len()).sum(),
+			rules : entry.occurrences.len(),
+			arities : entry.arities.iter().map(|(x, y)| (*x, *y)).collect()
+		})
+	}
+}
@@ -1,29 +1,167 @@
 pub mod reason;
+pub mod index;
+pub mod dtree;
+pub mod stream;
+pub mod proof;
+pub mod script;
+pub mod history;
+pub mod branch;
+pub mod dedup;
+pub mod graph;
+pub mod prune;
+pub mod goal;
 
 use cell::Cell;
-use std::fs::File;
-use std::io::Read;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{Read, Write};
 use misc::*;
 use gen::*;
-use error::SRLError;
+use navi::{CellID, CellPath, RuleHandle};
+use self::index::SymbolIndex;
+use self::dtree::DiscriminationTree;
+use self::stream::RuleReader;
+use self::proof::ProofStep;
+use self::goal::{Goal, Event};
+use error::{SRLError, DatabaseErrorKind, NavigationErrorKind};
 
+// everything is shared between clones (see branch.rs) and copied on the first write
 #[derive(Clone)]
 pub struct Database {
-	rules : Vec<Cell>,
-	src_rules_count : usize
+	rules : Arc<Vec<Arc<Cell>>>,
+	handles : Arc<Vec<RuleHandle>>, // handles[i] belongs to rules[i]; handles are handed out increasingly, so this is sorted
+	next_handle : Arc<AtomicU64>, // shared by all branches, so a handle names the same rule in every branch
+	src_rules_count : usize,
+	symbols : Arc<SymbolIndex>,
+	shapes : Arc<DiscriminationTree>,
+	proof_log : Arc<Vec<ProofStep>>,
+	normal_forms : Option<Arc<HashMap<Arc<Cell>, RuleHandle>>>, // rule -> first rule handle, only in dedup mode (see dedup.rs)
+	goals : Arc<Vec<Goal>>,
+	events : Vec<Event>, // not yet taken by take_events
+	redo_steps : Vec<ProofStep>, // undone law applications, the last one is redone first
+	transactions : Vec<Database> // snapshots taken by begin_transaction, innermost last
+}
+
+// separates the source rules from the derived rules in a saved database, see Database::write_to
+pub static DERIVED_MARKER : &'static str = "derived:";
+
+// the cells after it are goals instead of rules, see goal.rs
+pub static GOAL_MARKER : &'static str = "goal:";
+
+// handles are names for the current session, they are not part of the content
+impl PartialEq for Database {
+	fn eq(&self, other : &Database) -> bool {
+		self.rules == other.rules && self.src_rules_count == other.src_rules_count
+	}
+}
+
+// first line of a proof file written by Database::save
+static PROOF_CHECKSUM_PREFIX : &'static str = "// database ";
+
+// writes to a temporary file next to filename first and renames it, so filename is never left half-written
+fn save_file(filename : &str, write : impl FnOnce(&File) -> Result<(), SRLError>) -> Result<(), SRLError> {
+	let tmp_filename = format!("{}.tmp", filename);
+	let file : File = match File::create(&tmp_filename) {
+		Ok(file) => file,
+		Err(io_error) => return Err(SRLError::io("Database::save", &format!("Cannot create file: '{}'", tmp_filename), io_error))
+	};
+	write(&file)?;
+	if let Err(io_error) = file.sync_all() {
+		let _ = fs::remove_file(&tmp_filename);
+		return Err(SRLError::io("Database::save", &format!("failed to sync file: '{}'", tmp_filename), io_error));
+	}
+	if let Err(io_error) = fs::rename(&tmp_filename, filename) {
+		let _ = fs::remove_file(&tmp_filename);
+		return Err(SRLError::io("Database::save", &format!("failed to rename '{}' to '{}'", tmp_filename, filename), io_error));
+	}
+	Ok(())
 }
 
 impl Database {
+	// rules after a line containing only DERIVED_MARKER are not write protected,
+	// cells after a line containing only GOAL_MARKER are goals
 	pub fn by_string(string : &str) -> Result<Database, SRLError> {
-		use parse::*;
+		Database::from_reader(string.as_bytes())
+	}
 
-		let rule_strings = split_rules(string.trim().to_string());
-		let mut rules : Vec<Cell> = vec![scope(0, complex(vec![simple_by_str("="), var(0), var(0)]))];
-		for rule_string in rule_strings {
-			rules.push(Cell::by_string(&rule_string)?.get_normalized()?);
-		}
+	fn create(rules : Vec<Cell>, src_rules_count : usize) -> Database {
 		let len = rules.len();
-		Ok(Database { rules : rules, src_rules_count : len })
+		let handles : Vec<RuleHandle> = (0..len as u64).map(RuleHandle::create).collect();
+		let mut symbols = SymbolIndex::new();
+		let mut shapes = DiscriminationTree::new();
+		for (handle, rule) in handles.iter().zip(rules.iter()) {
+			symbols.add_rule(*handle, rule);
+			shapes.add_rule(*handle, rule);
+		}
+		Database {
+			rules : Arc::new(rules.into_iter().map(Arc::new).collect()),
+			handles : Arc::new(handles),
+			next_handle : Arc::new(AtomicU64::new(len as u64)),
+			src_rules_count : src_rules_count,
+			symbols : Arc::new(symbols),
+			shapes : Arc::new(shapes),
+			proof_log : Arc::new(Vec::new()),
+			normal_forms : None,
+			goals : Arc::new(Vec::new()),
+			events : Vec::new(),
+			redo_steps : Vec::new(),
+			transactions : Vec::new()
+		}
+	}
+
+	// writes the source rules (without the builtin rule 0), DERIVED_MARKER and the derived rules, one per line,
+	// then GOAL_MARKER and the goals, if there are any
+	// Database::by_string reads this back into an equal database
+	pub fn write_to(&self, mut writer : impl Write) -> Result<(), SRLError> {
+		let mut string = String::new();
+		for rule in &self.rules[1..self.src_rules_count] {
+			string.push_str(&rule.to_rule_string());
+			string.push('\n');
+		}
+		string.push_str(DERIVED_MARKER);
+		string.push('\n');
+		for rule in &self.rules[self.src_rules_count..] {
+			string.push_str(&rule.to_rule_string());
+			string.push('\n');
+		}
+		if !self.goals.is_empty() {
+			string.push_str(GOAL_MARKER);
+			string.push('\n');
+			for goal in self.goals.iter() {
+				string.push_str(&goal.get_cell().to_rule_string());
+				string.push('\n');
+			}
+		}
+		match writer.write_all(string.as_bytes()).and_then(|_| writer.flush()) {
+			Ok(()) => Ok(()),
+			Err(io_error) => Err(SRLError::io("Database::write_to", "failed to write database", io_error))
+		}
+	}
+
+	// writes the database to filename and its proof log (see write_saved_proof_log) to filename.proof,
+	// by_filename loads both again; without proof log an old filename.proof is removed.
+	// the proof file starts with the checksum of the database file, which is written last,
+	// so by_filename notices a proof file left behind by a save, that did not complete
+	pub fn save(&self, filename : &str) -> Result<(), SRLError> {
+		let mut database : Vec<u8> = Vec::new();
+		self.write_to(&mut database)?;
+		let proof_filename = format!("{}.proof", filename);
+		if self.proof_log.is_empty() {
+			let _ = fs::remove_file(&proof_filename);
+		} else {
+			save_file(&proof_filename, |mut file| {
+				if let Err(io_error) = write!(file, "{}{:016x}\n", PROOF_CHECKSUM_PREFIX, checksum(&database)) {
+					return Err(SRLError::io("Database::save", "failed to write proof log", io_error));
+				}
+				self.write_saved_proof_log(file)
+			})?;
+		}
+		save_file(filename, |mut file| match file.write_all(&database) {
+			Ok(()) => Ok(()),
+			Err(io_error) => Err(SRLError::io("Database::save", "failed to write database", io_error))
+		})
 	}
 
 	pub fn to_string(&self) -> String {
@@ -36,15 +174,44 @@ impl Database {
 	}
 
 	pub fn by_filename(filename : &str) -> Result<Database, SRLError> {
-		let mut file : File = match File::open(filename) {
-			Ok(file) => file,
-			Err(_) => return Err(SRLError("Database::by_filename".to_string(), format!("Cannot open file: '{}'", filename))),
+		let database = match fs::read(filename) {
+			Ok(x) => x,
+			Err(io_error) => return Err(SRLError::io("Database::by_filename", &format!("Cannot open file: '{}'", filename), io_error)),
 		};
-		let mut filecontent = String::new();
-		if let Err(_) = file.read_to_string(&mut filecontent) {
-			return Err(SRLError("Database::by_filename".to_string(), format!("failed to read from file: '{}'", filename)));
+		let mut db = Database::from_reader(&database[..])?;
+		let proof_filename = format!("{}.proof", filename);
+		if let Ok(proof_log) = fs::read_to_string(&proof_filename) {
+			let (header, proof_log) = proof_log.split_at(proof_log.find('\n').map_or(proof_log.len(), |x| x + 1));
+			if header.trim_end() != format!("{}{:016x}", PROOF_CHECKSUM_PREFIX, checksum(&database)) {
+				return Err(SRLError::parse("Database::by_filename", &format!("'{}' does not belong to '{}'", proof_filename, filename)));
+			}
+			db.load_proof_log(proof_log.as_bytes())?;
+		}
+		Ok(db)
+	}
+
+	// same format as Database::by_string, e.g. io::stdin()
+	pub fn from_reader(reader : impl Read) -> Result<Database, SRLError> {
+		let mut rules : Vec<Cell> = vec![scope(0, complex(vec![simple_by_str("="), var(0), var(0)]))];
+		let mut src_rules_count = None;
+		let mut goals = Vec::new();
+		for source_rule in RuleReader::new(reader) {
+			let source_rule = source_rule?;
+			if source_rule.is_goal() {
+				goals.push(source_rule.into_cell());
+				continue;
+			}
+			if source_rule.is_derived() && src_rules_count.is_none() {
+				src_rules_count = Some(rules.len());
+			}
+			rules.push(source_rule.into_cell());
 		}
-		Database::by_string(&filecontent)
+		let src_rules_count = src_rules_count.unwrap_or(rules.len());
+		let mut db = Database::create(rules, src_rules_count);
+		for goal in goals {
+			db.add_goal(goal)?;
+		}
+		Ok(db)
 	}
 
 	pub fn count_rules(&self) -> usize {
@@ -52,44 +219,135 @@ impl Database {
 	}
 
 	pub fn get_rules(&self) -> Vec<Cell> {
-		self.rules.clone()
+		self.rules.iter().map(|x| (**x).clone()).collect()
 	}
 
-	pub fn get_rule(&self, index : usize) -> Cell {
+	pub fn get_rule_ref(&self, index : usize) -> Option<&Cell> {
+		self.rules.get(index).map(|x| &**x)
+	}
+
+	pub fn try_get_rule(&self, index : usize) -> Result<Cell, SRLError> {
 		if ! index_in_len(index, self.rules.len()) {
-			panic!(format!("Database::get_rule({}): index out of range", index));
+			return Err(SRLError::database("Database::get_rule", DatabaseErrorKind::RuleOutOfRange));
+		}
+		Ok((*self.rules[index]).clone())
+	}
+
+	pub fn get_rule(&self, index : usize) -> Cell {
+		match self.try_get_rule(index) {
+			Ok(x) => x,
+			Err(srl_error) => panic!("{}", srl_error)
 		}
-		self.rules[index].clone()
 	}
 
+	// like every change outside of undo/redo, deleting a rule clears the redo steps
 	pub fn delete_rule(&mut self, index : usize) -> Result<(), SRLError> {
+		self.remove_rule(index)?;
+		self.redo_steps.clear();
+		Ok(())
+	}
+
+	pub fn delete_rule_by_handle(&mut self, handle : RuleHandle) -> Result<(), SRLError> {
+		let index = self.get_rule_index(handle)?;
+		self.delete_rule(index)
+	}
+
+	// delete_rule without touching the redo steps (used by undo)
+	fn remove_rule(&mut self, index : usize) -> Result<(), SRLError> {
 		if index_in_len(index, self.src_rules_count) {
-			return Err(SRLError("Database::delete_rule".to_string(), "This rule is write protected".to_string()))
+			return Err(SRLError::database("Database::delete_rule", DatabaseErrorKind::WriteProtected))
 		}
 		if index_in_len(index, self.count_rules()) {
-			self.rules.remove(index);
+			let rule = Arc::make_mut(&mut self.rules).remove(index);
+			let handle = Arc::make_mut(&mut self.handles).remove(index);
+			Arc::make_mut(&mut self.symbols).delete_rule(handle, &rule);
+			Arc::make_mut(&mut self.shapes).delete_rule(handle, &rule);
+			self.forget_normal_form(handle, &rule);
+			self.check_goal_proofs();
 			return Ok(());
 		}
-		return Err(SRLError("Database::delete_rule".to_string(), "out of range".to_string()))
+		return Err(SRLError::database("Database::delete_rule", DatabaseErrorKind::RuleOutOfRange))
 	}
 
-	pub fn contains_cellname(&self, string : &str) -> bool {
-		fn cell_has_string(cell : &Cell, tuple : (String, bool)) -> (String, bool) {
-			let (string, b) = tuple;
-			if b {
-				return (string, true);
-			}
-			if let Cell::Simple { string : string2 } = cell.clone() {
-				return (string.clone(), string == string2.get_string());
-			}
-			(string, false)
+	fn push_rule(&mut self, rule : Cell) -> RuleHandle {
+		let handle = RuleHandle::create(self.next_handle.fetch_add(1, Ordering::SeqCst));
+		self.insert_rule_with_handle(Arc::new(rule), handle);
+		handle
+	}
+
+	// the rule is placed by its handle, so the handles stay sorted; returns its index
+	fn insert_rule_with_handle(&mut self, rule : Arc<Cell>, handle : RuleHandle) -> usize {
+		let index = match self.handles.binary_search(&handle) {
+			Ok(_) => panic!("Database::insert_rule_with_handle: handle is in use - should not happen"),
+			Err(x) => x
+		};
+		Arc::make_mut(&mut self.symbols).add_rule(handle, &rule);
+		Arc::make_mut(&mut self.shapes).add_rule(handle, &rule);
+		self.add_normal_form(handle, &rule);
+		Arc::make_mut(&mut self.rules).insert(index, rule);
+		Arc::make_mut(&mut self.handles).insert(index, handle);
+		index
+	}
+
+	// the CellPath of id, without resolving its handle
+	fn get_path(&self, id : &CellID) -> Result<CellPath, SRLError> {
+		match self.rules.get(id.get_rule_id()) {
+			Some(x) => CellPath::create((**x).clone(), id.get_indices()),
+			None => Err(SRLError::navigation("CellID::to_path", NavigationErrorKind::RuleOutOfRange))
 		}
-		for rule in self.rules.clone() {
-			let (_, b) = rule.recurse::<(String, bool)>((string.to_string(), false), cell_has_string);
-			if b {
-				return true;
-			}
+	}
+
+	pub fn get_handles(&self) -> Vec<RuleHandle> {
+		(*self.handles).clone()
+	}
+
+	pub fn get_rule_handle(&self, index : usize) -> Result<RuleHandle, SRLError> {
+		match self.handles.get(index) {
+			Some(x) => Ok(*x),
+			None => Err(SRLError::database("Database::get_rule_handle", DatabaseErrorKind::RuleOutOfRange))
+		}
+	}
+
+	// the current position of the rule, fails if it has been deleted
+	pub fn get_rule_index(&self, handle : RuleHandle) -> Result<usize, SRLError> {
+		match self.handles.binary_search(&handle) {
+			Ok(x) => Ok(x),
+			Err(_) => Err(SRLError::database("Database::get_rule_index", DatabaseErrorKind::UnknownHandle { id : handle.get_id() }))
 		}
-		false
+	}
+
+	pub fn get_rule_by_handle(&self, handle : RuleHandle) -> Result<Cell, SRLError> {
+		let index = self.get_rule_index(handle)?;
+		self.try_get_rule(index)
+	}
+
+	// a CellID that stays valid when other rules are deleted
+	pub fn get_cell_id(&self, index : usize, indices : Vec<usize>) -> Result<CellID, SRLError> {
+		let handle = self.get_rule_handle(index)?;
+		Ok(CellID::create(index, indices).with_handle(handle))
+	}
+
+	pub fn contains_cellname(&self, string : &str) -> bool {
+		self.symbols.contains(string)
+	}
+
+	pub fn get_symbol_index(&self) -> &SymbolIndex {
+		&self.symbols
+	}
+
+	// every occurence of the simple cell string, by rule position
+	pub fn find_symbol(&self, string : &str) -> Vec<CellID> {
+		self.symbols.get_occurrences(string)
+			.into_iter()
+			.filter_map(|(handle, indices)| self.get_rule_index(handle).ok().map(|x| CellID::create(x, indices)))
+			.collect()
+	}
+
+	// every subcell of every rule, that may match term (Vars on both sides are wildcards, see dtree.rs)
+	pub fn find_candidates(&self, term : &Cell) -> Vec<CellID> {
+		self.shapes.get_candidates(term)
+			.into_iter()
+			.filter_map(|(handle, indices)| self.get_rule_index(handle).ok().map(|x| CellID::create(x, indices)))
+			.collect()
 	}
 }
@@ -0,0 +1,383 @@
+use std::fmt;
+use std::mem;
+use std::io::{Read, Write};
+use std::str::FromStr;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::sync::atomic::Ordering;
+use cell::Cell;
+use error::{SRLError, DatabaseErrorKind, LawError, LawErrorKind};
+use navi::{CellID, RuleHandle};
+use kernel::{Certificate, Step};
+use super::Database;
+use super::reason::Law;
+pub use law::LawArgument;
+
+// text form of a proof step, one per line:
+//   <law> <input CellIDs> [<argument>] -> <result CellID> <resulting rule>
+// e.g.
+//   add_eqt #1@1/0 -> #2@2/ {0 (= 'true' wow)}.
+//   scope_insertion #3@3/2 (p a) -> #4@4/ = 'false' (= 'true' (p a)).
+//   scope_creation #1@1/ /2 /1.2 -> #5@5/ ...
+// paths (of scope_creation) are written like the indices of a CellID, with a leading '/'
+
+// a law together with everything it was applied to
+#[derive(Clone, PartialEq, Debug)]
+pub struct LawApplication {
+	law : Law,
+	inputs : Vec<CellID>,
+	argument : Option<LawArgument>
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct ProofStep {
+	application : LawApplication,
+	result : CellID, // root of the derived rule, carries its handle
+	rule : Arc<Cell> // shared with the database
+}
+
+pub(super) fn input_count(law : Law) -> usize {
+	match law {
+		Law::EqualsLaw | Law::EqualsLawImpl | Law::ImplicationsDerivation => 2,
+		_ => 1
+	}
+}
+
+fn parse_path(string : &str) -> Result<Vec<usize>, SRLError> {
+	if !string.starts_with('/') {
+		return Err(SRLError::parse("LawApplication::from_str", &format!("path '{}' has to start with '/'", string)));
+	}
+	let mut path = Vec::new();
+	if string.len() > 1 {
+		for index in string[1..].split('.') {
+			match index.parse::<usize>() {
+				Ok(x) => path.push(x),
+				Err(_) => return Err(SRLError::parse("LawApplication::from_str", &format!("'{}' is no number", index)))
+			}
+		}
+	}
+	Ok(path)
+}
+
+impl LawApplication {
+	pub fn create(law : Law, inputs : Vec<CellID>, argument : Option<LawArgument>) -> LawApplication {
+		LawApplication { law : law, inputs : inputs, argument : argument }
+	}
+
+	pub fn get_law(&self) -> Law { self.law }
+	pub fn get_inputs(&self) -> Vec<CellID> { self.inputs.clone() }
+	pub fn get_argument(&self) -> Option<LawArgument> { self.argument.clone() }
+
+	// runs the law on db, fails if the argument does not fit the law
+	pub fn apply(&self, db : &mut Database) -> Result<Cell, SRLError> {
+		db.apply(self)
+	}
+
+	// the rule, that apply would add, without changing db
+	pub fn preview(&self, db : &Database) -> Result<Cell, SRLError> {
+		db.preview(self)
+	}
+
+	pub(super) fn with_inputs(&self, inputs : Vec<CellID>) -> LawApplication {
+		LawApplication { law : self.law, inputs : inputs, argument : self.argument.clone() }
+	}
+}
+
+impl fmt::Display for LawApplication {
+	fn fmt(&self, f : &mut fmt::Formatter) -> fmt::Result {
+		f.write_str(self.law.get_name())?;
+		for input in &self.inputs {
+			write!(f, " {}", input)?;
+		}
+		if let Some(ref argument) = self.argument {
+			write!(f, " {}", argument)?;
+		}
+		Ok(())
+	}
+}
+
+impl FromStr for LawApplication {
+	type Err = SRLError;
+
+	fn from_str(string : &str) -> Result<LawApplication, SRLError> {
+		let mut tokens = string.split_whitespace();
+		let law = match tokens.next().and_then(Law::by_name) {
+			Some(x) => x,
+			None => return Err(SRLError::parse("LawApplication::from_str", &format!("unknown law in '{}'", string.trim())))
+		};
+		let mut inputs = Vec::new();
+		for _ in 0..input_count(law) {
+			match tokens.next() {
+				Some(x) => inputs.push(x.parse::<CellID>()?),
+				None => return Err(SRLError::parse("LawApplication::from_str", &format!("{} needs {} CellIDs", law.get_name(), input_count(law))))
+			}
+		}
+		let rest : Vec<&str> = tokens.collect();
+		let argument = match law {
+			Law::ScopeInsertion | Law::CaseCreation => Some(LawArgument::Cell(Cell::by_string(&rest.join(" "))?)),
+			Law::ScopeCreation => {
+				let mut paths = Vec::new();
+				for x in &rest {
+					paths.push(parse_path(x)?);
+				}
+				Some(LawArgument::Paths(paths))
+			},
+			Law::Declaration => {
+				if rest.len() != 1 {
+					return Err(SRLError::parse("LawApplication::from_str", "declaration needs exactly one name"));
+				}
+				Some(LawArgument::Name(rest[0].to_string()))
+			},
+			_ => {
+				if !rest.is_empty() {
+					return Err(SRLError::parse("LawApplication::from_str", &format!("unexpected argument '{}'", rest.join(" "))));
+				}
+				None
+			}
+		};
+		Ok(LawApplication::create(law, inputs, argument))
+	}
+}
+
+impl ProofStep {
+	pub fn get_application(&self) -> &LawApplication { &self.application }
+	pub fn get_law(&self) -> Law { self.application.law }
+	pub fn get_inputs(&self) -> Vec<CellID> { self.application.get_inputs() }
+	pub fn get_argument(&self) -> Option<LawArgument> { self.application.get_argument() }
+	pub fn get_result(&self) -> CellID { self.result.clone() }
+	pub fn get_rule(&self) -> Cell { (*self.rule).clone() }
+	pub(super) fn get_shared_rule(&self) -> Arc<Cell> { self.rule.clone() }
+
+	// applies f to the CellIDs of the inputs and the result
+	pub(super) fn with_mapped_ids(&self, f : impl Fn(&CellID) -> CellID) -> ProofStep {
+		let inputs = self.application.inputs.iter().map(&f).collect();
+		ProofStep { application : self.application.with_inputs(inputs), result : f(&self.result), rule : self.rule.clone() }
+	}
+
+	// replaces the handles of the inputs and the result, that occur in map
+	pub(super) fn with_mapped_handles(&self, map : &HashMap<RuleHandle, RuleHandle>) -> ProofStep {
+		self.with_mapped_ids(|id| match id.get_handle().and_then(|x| map.get(&x)) {
+			Some(&handle) => id.clone().with_handle(handle),
+			None => id.clone()
+		})
+	}
+}
+
+impl fmt::Display for ProofStep {
+	fn fmt(&self, f : &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "{} -> {} {}", self.application, self.result, self.rule.to_rule_string())
+	}
+}
+
+impl FromStr for ProofStep {
+	type Err = SRLError;
+
+	fn from_str(string : &str) -> Result<ProofStep, SRLError> {
+		let (application, result) = match string.find(" -> ") {
+			Some(x) => (&string[..x], string[x+4..].trim()),
+			None => return Err(SRLError::parse("ProofStep::from_str", "missing ' -> '"))
+		};
+		let (result_id, rule) = match result.find(' ') {
+			Some(x) => (&result[..x], result[x+1..].trim()),
+			None => return Err(SRLError::parse("ProofStep::from_str", "missing resulting rule"))
+		};
+		if !rule.ends_with('.') {
+			return Err(SRLError::parse("ProofStep::from_str", "resulting rule does not end with '.'"));
+		}
+		Ok(ProofStep {
+			application : application.parse()?,
+			result : result_id.parse()?,
+			rule : Arc::new(Cell::by_string(&rule[..rule.len()-1])?)
+		})
+	}
+}
+
+pub fn parse_proof_log(string : &str) -> Result<Vec<ProofStep>, SRLError> {
+	string.lines()
+		.filter(|x| !x.trim().is_empty())
+		.map(|x| x.parse())
+		.collect()
+}
+
+impl Database {
+	// called by the laws, the inputs get the handles of their rules
+	pub(super) fn record_proof_step(&mut self, application : LawApplication, rule_index : usize) -> Result<(), SRLError> {
+		let mut inputs = Vec::new();
+		for input in application.get_inputs() {
+			inputs.push(self.get_cell_id(input.get_rule_id(), input.get_indices())?);
+		}
+		let step = ProofStep {
+			application : application.with_inputs(inputs),
+			result : self.get_cell_id(rule_index, vec![])?,
+			rule : match self.rules.get(rule_index) {
+				Some(x) => x.clone(),
+				None => return Err(SRLError::database("Database::record_proof_step", DatabaseErrorKind::RuleOutOfRange))
+			}
+		};
+		Arc::make_mut(&mut self.proof_log).push(step);
+		Ok(())
+	}
+
+	// every successful law application in order, including those whose result has been deleted since
+	pub fn get_proof_log(&self) -> &[ProofStep] {
+		&self.proof_log
+	}
+
+	// how the rule at index was derived, None for source rules
+	pub fn get_proof_step(&self, index : usize) -> Option<&ProofStep> {
+		let handle = match self.get_rule_handle(index) {
+			Ok(x) => x,
+			Err(_) => return None
+		};
+		self.proof_log.iter().find(|x| x.result.get_handle() == Some(handle))
+	}
+
+	// step with the CellIDs pointing to the current rule positions of their handles, used after rules were removed
+	pub(super) fn with_current_positions(&self, step : &ProofStep) -> ProofStep {
+		step.with_mapped_ids(|id| match id.get_handle().and_then(|h| self.get_rule_index(h).ok().map(|index| (h, index))) {
+			Some((handle, index)) => CellID::create(index, id.get_indices()).with_handle(handle),
+			None => id.clone() // deleted earlier
+		})
+	}
+
+	pub fn write_proof_log(&self, writer : impl Write) -> Result<(), SRLError> {
+		Database::write_steps(self.proof_log.iter(), writer)
+	}
+
+	fn write_steps<'a>(steps : impl Iterator<Item=&'a ProofStep>, mut writer : impl Write) -> Result<(), SRLError> {
+		for step in steps {
+			if let Err(io_error) = writeln!(writer, "{}", step) {
+				return Err(SRLError::io("Database::write_proof_log", "failed to write proof log", io_error));
+			}
+		}
+		Ok(())
+	}
+}
+
+impl Database {
+	// the proof log as it fits the database written by write_to once it is loaded again:
+	// there the rules get the handles 0, 1, 2, .. in order, deleted results get the handles after them
+	pub fn write_saved_proof_log(&self, writer : impl Write) -> Result<(), SRLError> {
+		let mut map : HashMap<RuleHandle, usize> = HashMap::new();
+		for (index, handle) in self.handles.iter().enumerate() {
+			map.insert(*handle, index);
+		}
+		for step in self.proof_log.iter() {
+			let ids = step.application.inputs.iter().chain(Some(&step.result));
+			for handle in ids.filter_map(|x| x.get_handle()) {
+				let next = map.len();
+				map.entry(handle).or_insert(next);
+			}
+		}
+		let steps : Vec<ProofStep> = self.proof_log.iter()
+			.map(|step| step.with_mapped_ids(|id| match id.get_handle() {
+				Some(handle) => {
+					let new = map[&handle];
+					let rule_id = if new < self.count_rules() { new } else { id.get_rule_id() }; // deleted rules keep their old position
+					CellID::create(rule_id, id.get_indices()).with_handle(RuleHandle::create(new as u64))
+				},
+				None => id.clone()
+			}))
+			.collect();
+		Database::write_steps(steps.iter(), writer)
+	}
+
+	// reads a proof log written by write_saved_proof_log, the database must not have a proof log yet
+	// -- fails if a logged result is in the database, but differs from the logged rule
+	pub fn load_proof_log(&mut self, mut reader : impl Read) -> Result<(), SRLError> {
+		let mut string = String::new();
+		if let Err(io_error) = reader.read_to_string(&mut string) {
+			return Err(SRLError::io("Database::load_proof_log", "failed to read proof log", io_error));
+		}
+		if !self.proof_log.is_empty() {
+			return Err(SRLError::parse("Database::load_proof_log", "the database has a proof log already"));
+		}
+		let mut steps = parse_proof_log(&string)?;
+		let mut next_handle = 0;
+		for step in &mut steps {
+			for id in step.application.inputs.iter().chain(Some(&step.result)) {
+				let handle = match id.get_handle() {
+					Some(x) => x,
+					None => return Err(SRLError::parse("Database::load_proof_log", &format!("'{}' has no handle", id)))
+				};
+				next_handle = ::std::cmp::max(next_handle, handle.get_id() + 1);
+			}
+			if let Some(index) = step.result.get_handle().and_then(|x| self.get_rule_index(x).ok()) {
+				if *self.rules[index] != *step.rule {
+					return Err(SRLError::parse("Database::load_proof_log", &format!("rule {} differs from the proof log", index)));
+				}
+				step.rule = self.rules[index].clone(); // shared with the database, like in record_proof_step
+			}
+		}
+		self.next_handle.fetch_max(next_handle, Ordering::SeqCst);
+		self.proof_log = Arc::new(steps);
+		self.redo_steps.clear();
+		Ok(())
+	}
+
+	// the rules, that were not derived in this session (rule 0, source rules and loaded derived rules), are the axioms,
+	// the proof log gives the steps
+	// -- fails if a step uses a rule, that has been deleted before being derived
+	pub fn get_certificate(&self) -> Result<Certificate, SRLError> {
+		self.build_certificate().map_err(|x| x.1)
+	}
+
+	fn build_certificate(&self) -> Result<Certificate, (usize, SRLError)> {
+		let derived : HashSet<RuleHandle> = self.proof_log.iter().filter_map(|x| x.result.get_handle()).collect();
+		let mut theorems : HashMap<RuleHandle, usize> = HashMap::new();
+		let mut axioms = Vec::new();
+		for (rule, handle) in self.rules.iter().zip(self.handles.iter()) {
+			if !derived.contains(handle) {
+				theorems.insert(*handle, axioms.len());
+				axioms.push((**rule).clone());
+			}
+		}
+
+		// a deleted rule is forgotten after the last step using it, the deletion time is not logged,
+		// but the declarations accepted by the database were applied after it (see declaration_rule)
+		let mut last_uses : HashMap<RuleHandle, usize> = HashMap::new();
+		for (i, step) in self.proof_log.iter().enumerate() {
+			for handle in step.application.inputs.iter().chain(Some(&step.result)).filter_map(|x| x.get_handle()) {
+				last_uses.insert(handle, i);
+			}
+		}
+		let mut forgotten : Vec<Vec<usize>> = vec![Vec::new(); self.proof_log.len()];
+
+		let mut steps = Vec::new();
+		for (i, step) in self.proof_log.iter().enumerate() {
+			let mut inputs = Vec::new();
+			for input in &step.application.inputs {
+				let handle = match input.get_handle() {
+					Some(x) => x,
+					None => panic!("Database::get_certificate: proof log input without handle - should not happen")
+				};
+				match theorems.get(&handle) {
+					Some(&x) => inputs.push((x, input.get_indices())),
+					None => return Err((i, SRLError::database("Database::get_certificate", DatabaseErrorKind::UnknownHandle { id : handle.get_id() })))
+				}
+			}
+			steps.push(Step::create(step.get_law(), inputs, step.get_argument()).with_forgotten(mem::replace(&mut forgotten[i], Vec::new())));
+			if let Some(handle) = step.result.get_handle() {
+				theorems.insert(handle, axioms.len() + i);
+				if self.get_rule_index(handle).is_err() {
+					if let Some(x) = forgotten.get_mut(last_uses[&handle] + 1) {
+						x.push(axioms.len() + i);
+					}
+				}
+			}
+		}
+		Ok(Certificate::create(axioms, steps))
+	}
+
+	// rechecks the proof log with the kernel, returns the index of the first wrong step
+	pub fn verify_proof_log(&self) -> Result<(), (usize, SRLError)> {
+		let results = self.build_certificate()?.check()?;
+		for (i, (step, result)) in self.proof_log.iter().zip(results.into_iter()).enumerate() {
+			if *step.rule != result {
+				let law_error = LawError::create(step.get_law(), LawErrorKind::CellMismatch, "logged rule differs from the kernel's result").with_cells(result, step.get_rule());
+				return Err((i, law_error.into()));
+			}
+		}
+		Ok(())
+	}
+}
@@ -0,0 +1,51 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+use error::{SRLError, DatabaseErrorKind};
+use navi::RuleHandle;
+use super::Database;
+
+impl Database {
+	// deletes every derived rule, that is neither in keep nor a (transitive) dependency of a rule in keep,
+	// together with its proof step; returns how many rules were deleted.
+	// the CellIDs of the remaining proof steps get the new rule positions, source rules are never deleted
+	pub fn prune(&mut self, keep : &[usize]) -> Result<usize, SRLError> {
+		let graph = self.get_dependency_graph();
+		let mut needed : HashSet<RuleHandle> = HashSet::new();
+		for &index in keep {
+			let handle = match self.handles.get(index) {
+				Some(x) => *x,
+				None => return Err(SRLError::database("Database::prune", DatabaseErrorKind::RuleOutOfRange))
+			};
+			needed.insert(handle);
+			needed.extend(graph.get_dependencies(handle));
+		}
+
+		let mut removed = Vec::new();
+		{
+			let old_rules = ::std::mem::replace(Arc::make_mut(&mut self.rules), Vec::new());
+			let old_handles = ::std::mem::replace(Arc::make_mut(&mut self.handles), Vec::new());
+			for (index, (rule, handle)) in old_rules.into_iter().zip(old_handles.into_iter()).enumerate() {
+				if index < self.src_rules_count || needed.contains(&handle) {
+					Arc::make_mut(&mut self.rules).push(rule);
+					Arc::make_mut(&mut self.handles).push(handle);
+				} else {
+					removed.push((handle, rule));
+				}
+			}
+		}
+		for &(handle, ref rule) in &removed {
+			Arc::make_mut(&mut self.symbols).delete_rule(handle, rule);
+			Arc::make_mut(&mut self.shapes).delete_rule(handle, rule);
+			self.forget_normal_form(handle, rule);
+		}
+
+		let proof_log = self.proof_log.iter()
+			.filter(|x| x.get_result().get_handle().map_or(false, |h| needed.contains(&h)))
+			.map(|x| self.with_current_positions(x))
+			.collect();
+		self.proof_log = Arc::new(proof_log);
+		self.redo_steps.clear();
+		self.check_goal_proofs();
+		Ok(removed.len())
+	}
+}
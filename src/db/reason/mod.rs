@@ -1,37 +1,181 @@
 mod wrapper;
 
+pub use self::wrapper::Wrapper;
+pub use law::Law;
+
 use super::Database;
 use cell::Cell;
-use error::SRLError;
+use cell::CellType;
+use error::{SRLError, LawError, LawErrorKind};
 use gen::*;
-use navi::CellID;
-use navi::CellPath;
+use navi::{CellID, CellPath};
+use query::CellLocator;
+use super::proof::{LawApplication, LawArgument, input_count};
 
 impl Database {
-	fn add_rule(&mut self, rule : Cell) -> Result<Cell, SRLError> {
+	fn add_rule(&mut self, rule : Cell, application : LawApplication) -> Result<Cell, SRLError> {
 		let norm = rule.get_normalized()?;
-		self.rules.push(norm.clone());
+		if self.is_dedup() && self.find_rule(&norm).is_some() {
+			return Ok(norm);
+		}
+		let handle = self.push_rule(norm.clone());
+		let index = self.count_rules() - 1;
+		self.record_proof_step(application, index)?;
+		self.redo_steps.clear();
+		self.check_new_rule(handle, &norm);
 		Ok(norm)
 	}
 
+	// the normalized rule, that applying the law would add, or why the law is not applicable; the database stays unchanged
+	pub fn preview(&self, application : &LawApplication) -> Result<Cell, SRLError> {
+		let (_, rule) = self.derive_rule(application)?;
+		rule.get_normalized()
+	}
+
+	// like preview, but adds the rule and records the application in the proof log.
+	// apply and the law methods below return the rule, not where it is: in dedup mode a rule, that exists already,
+	// is not added, Database::derive is the entry point returning the index of the new or the existing rule
+	pub fn apply(&mut self, application : &LawApplication) -> Result<Cell, SRLError> {
+		let (application, rule) = self.derive_rule(application)?;
+		self.add_rule(rule, application)
+	}
+
+	// every law application, checked or applied, goes through here; returns the application with located inputs
+	fn derive_rule(&self, application : &LawApplication) -> Result<(LawApplication, Cell), SRLError> {
+		let law = application.get_law();
+		let argument = application.get_argument();
+		let wrong_arguments = || SRLError::from(LawError::create(law, LawErrorKind::WrongArguments, "wrong arguments"));
+		let mut inputs = Vec::new();
+		for input in application.get_inputs() {
+			inputs.push(input.locate(self)?);
+		}
+		if inputs.len() != input_count(law) {
+			return Err(wrong_arguments());
+		}
+		let rule = match (law, &argument) {
+			(Law::EqualsLaw, &None) => self.equals_law_rule(&inputs[0], &inputs[1]),
+			(Law::EqualsLawImpl, &None) => self.equals_law_impl_rule(&inputs[0], &inputs[1]),
+			(Law::InequalConstants, &None) => self.inequal_constants_rule(&inputs[0]),
+			(Law::AddEqt, &None) => self.add_eqt_rule(&inputs[0]),
+			(Law::RmEqt, &None) => self.rm_eqt_rule(&inputs[0]),
+			(Law::ScopeInsertion, &Some(LawArgument::Cell(ref cell))) => self.scope_insertion_rule(&inputs[0], cell),
+			(Law::ScopeCreation, &Some(LawArgument::Paths(ref paths))) => self.scope_creation_rule(&inputs[0], paths),
+			(Law::ImplicationsDerivation, &None) => self.implications_derivation_rule(&inputs[0], &inputs[1]),
+			(Law::ScopeExchange, &None) => self.scope_exchange_rule(&inputs[0]),
+			(Law::CaseCreation, &Some(LawArgument::Cell(ref cell))) => self.case_creation_rule(&inputs[0], cell),
+			(Law::Declaration, &Some(LawArgument::Name(ref name))) => self.declaration_rule(&inputs[0], name),
+			_ => return Err(wrong_arguments())
+		}?;
+		Ok((LawApplication::create(law, inputs, argument), rule))
+	}
+
+	// check_<law> previews <law>, <law> applies it (see apply for dedup mode)
+	pub fn check_equals_law<L1 : CellLocator, L2 : CellLocator>(&self, src_id : L1, evidence_id : L2) -> Result<Cell, SRLError> {
+		self.preview(&LawApplication::create(Law::EqualsLaw, vec![src_id.locate(self)?, evidence_id.locate(self)?], None))
+	}
+
+	pub fn equals_law<L1 : CellLocator, L2 : CellLocator>(&mut self, src_id : L1, evidence_id : L2) -> Result<Cell, SRLError> {
+		self.apply(&LawApplication::create(Law::EqualsLaw, vec![src_id.locate(self)?, evidence_id.locate(self)?], None))
+	}
+
+	pub fn check_equals_law_impl<L1 : CellLocator, L2 : CellLocator>(&self, src_id : L1, evidence_id : L2) -> Result<Cell, SRLError> {
+		self.preview(&LawApplication::create(Law::EqualsLawImpl, vec![src_id.locate(self)?, evidence_id.locate(self)?], None))
+	}
+
+	pub fn equals_law_impl<L1 : CellLocator, L2 : CellLocator>(&mut self, src_id : L1, evidence_id : L2) -> Result<Cell, SRLError> {
+		self.apply(&LawApplication::create(Law::EqualsLawImpl, vec![src_id.locate(self)?, evidence_id.locate(self)?], None))
+	}
+
+	pub fn check_inequal_constants<L : CellLocator>(&self, id : L) -> Result<Cell, SRLError> {
+		self.preview(&LawApplication::create(Law::InequalConstants, vec![id.locate(self)?], None))
+	}
+
+	pub fn inequal_constants<L : CellLocator>(&mut self, id : L) -> Result<Cell, SRLError> {
+		self.apply(&LawApplication::create(Law::InequalConstants, vec![id.locate(self)?], None))
+	}
+
+	pub fn check_add_eqt<L : CellLocator>(&self, cell_id : L) -> Result<Cell, SRLError> {
+		self.preview(&LawApplication::create(Law::AddEqt, vec![cell_id.locate(self)?], None))
+	}
+
+	pub fn add_eqt<L : CellLocator>(&mut self, cell_id : L) -> Result<Cell, SRLError> {
+		self.apply(&LawApplication::create(Law::AddEqt, vec![cell_id.locate(self)?], None))
+	}
+
+	pub fn check_rm_eqt<L : CellLocator>(&self, cell_id : L) -> Result<Cell, SRLError> {
+		self.preview(&LawApplication::create(Law::RmEqt, vec![cell_id.locate(self)?], None))
+	}
+
+	pub fn rm_eqt<L : CellLocator>(&mut self, cell_id : L) -> Result<Cell, SRLError> {
+		self.apply(&LawApplication::create(Law::RmEqt, vec![cell_id.locate(self)?], None))
+	}
+
+	pub fn check_scope_insertion<L : CellLocator>(&self, scope_id : L, cell : Cell) -> Result<Cell, SRLError> {
+		self.preview(&LawApplication::create(Law::ScopeInsertion, vec![scope_id.locate(self)?], Some(LawArgument::Cell(cell))))
+	}
+
+	pub fn scope_insertion<L : CellLocator>(&mut self, scope_id : L, cell : Cell) -> Result<Cell, SRLError> {
+		self.apply(&LawApplication::create(Law::ScopeInsertion, vec![scope_id.locate(self)?], Some(LawArgument::Cell(cell))))
+	}
+
+	pub fn check_scope_creation<L : CellLocator>(&self, scope_id : L, indices : Vec<Vec<usize>>) -> Result<Cell, SRLError> {
+		self.preview(&LawApplication::create(Law::ScopeCreation, vec![scope_id.locate(self)?], Some(LawArgument::Paths(indices))))
+	}
+
+	pub fn scope_creation<L : CellLocator>(&mut self, scope_id : L, indices : Vec<Vec<usize>>) -> Result<Cell, SRLError> {
+		self.apply(&LawApplication::create(Law::ScopeCreation, vec![scope_id.locate(self)?], Some(LawArgument::Paths(indices))))
+	}
+
+	pub fn check_implications_derivation<L1 : CellLocator, L2 : CellLocator>(&self, case_id : L1, case_negation_id : L2) -> Result<Cell, SRLError> {
+		self.preview(&LawApplication::create(Law::ImplicationsDerivation, vec![case_id.locate(self)?, case_negation_id.locate(self)?], None))
+	}
+
+	pub fn implications_derivation<L1 : CellLocator, L2 : CellLocator>(&mut self, case_id : L1, case_negation_id : L2) -> Result<Cell, SRLError> {
+		self.apply(&LawApplication::create(Law::ImplicationsDerivation, vec![case_id.locate(self)?, case_negation_id.locate(self)?], None))
+	}
+
+	pub fn check_scope_exchange<L : CellLocator>(&self, outer_scope_id : L) -> Result<Cell, SRLError> {
+		self.preview(&LawApplication::create(Law::ScopeExchange, vec![outer_scope_id.locate(self)?], None))
+	}
+
+	pub fn scope_exchange<L : CellLocator>(&mut self, outer_scope_id : L) -> Result<Cell, SRLError> {
+		self.apply(&LawApplication::create(Law::ScopeExchange, vec![outer_scope_id.locate(self)?], None))
+	}
+
+	pub fn check_case_creation<L : CellLocator>(&self, cell_id : L, arg_cell : Cell) -> Result<Cell, SRLError> {
+		self.preview(&LawApplication::create(Law::CaseCreation, vec![cell_id.locate(self)?], Some(LawArgument::Cell(arg_cell))))
+	}
+
+	pub fn case_creation<L : CellLocator>(&mut self, cell_id : L, arg_cell : Cell) -> Result<Cell, SRLError> {
+		self.apply(&LawApplication::create(Law::CaseCreation, vec![cell_id.locate(self)?], Some(LawArgument::Cell(arg_cell))))
+	}
+
+	pub fn check_declaration<L : CellLocator>(&self, cell_id : L, string : &str) -> Result<Cell, SRLError> {
+		self.preview(&LawApplication::create(Law::Declaration, vec![cell_id.locate(self)?], Some(LawArgument::Name(string.to_string()))))
+	}
+
+	pub fn declaration<L : CellLocator>(&mut self, cell_id : L, string : &str) -> Result<Cell, SRLError> {
+		self.apply(&LawApplication::create(Law::Declaration, vec![cell_id.locate(self)?], Some(LawArgument::Name(string.to_string()))))
+	}
+
 	// src_id = "The cell that has to be replaced" | `{0 (<p> 0)}.`
 	// evidence_id = "the equals cell"		  | `{0 <(= p q)>}`
-	pub fn equals_law(&mut self, src_id : CellID, evidence_id : CellID) -> Result<Cell, SRLError> {
-		let src_path = src_id.get_path(&self.rules)?;
-		let evidence_path = evidence_id.get_path(&self.rules)?;
+	fn equals_law_rule(&self, src_id : &CellID, evidence_id : &CellID) -> Result<Cell, SRLError> {
+		let src_path = self.get_path(&src_id)?;
+		let evidence_path = self.get_path(&evidence_id)?;
 
 		let wrapper = match evidence_path.get_wrapper() {
 			Some(x) => x,
-			None => return Err(SRLError("equals_law".to_string(), "evidence_id is not in wrapper".to_string()))
+			None => return Err(LawError::create(Law::EqualsLaw, LawErrorKind::NoWrapper, "evidence_id is not in wrapper").with_path(&evidence_path).into())
 		};
 		if !wrapper.is_nexq() {
-			return Err(SRLError("equals_law".to_string(), "wrapper is no nexq-wrapper".to_string()));
+			return Err(LawError::create(Law::EqualsLaw, LawErrorKind::WrapperNotNexq, "wrapper is no nexq-wrapper").with_path(&evidence_path).into());
 		}
 		let evi_cell = evidence_path.get_cell();
 		let (a, b) = evi_cell.get_equals_cell_arguments()?;
 
 		if !wrapper.is_around(&src_path) {
-			return Err(SRLError("equals_law".to_string(), "src_id and evidence_id are not in the same wrapper".to_string()));
+			return Err(LawError::create(Law::EqualsLaw, LawErrorKind::NotInWrapper, "src_id and evidence_id are not in the same wrapper").with_path(&src_path).into());
 		}
 
 		let src_cell = src_path.get_cell();
@@ -42,37 +186,37 @@ impl Database {
 		} else if b.matches(&src_cell) {
 			new = a;
 		} else {
-			return Err(SRLError("equals_law".to_string(), "replace cell does not occur in evidence".to_string()));
+			return Err(LawError::create(Law::EqualsLaw, LawErrorKind::NoMatch, "replace cell does not occur in evidence").with_path(&src_path).with_cells(evi_cell.clone(), src_cell.clone()).into());
 		}
 
-		let rule = src_path.replace_by(new);
-		self.add_rule(rule)
+		let rule = src_path.try_replace_by(new)?;
+		Ok(rule)
 	}
 
 	// src_id = "The cell that has to be replaced" | `{0 [=> (= p q) (<p> 0)]}.`
 	// evidence_id = "the equals cell"		  | `{0 [=> <(= p q)> (p 0)]}`
-	pub fn equals_law_impl(&mut self, src_id : CellID, evidence_id : CellID) -> Result<Cell, SRLError> {
-		let src_path = src_id.get_path(&self.rules)?;
-		let evidence_path = evidence_id.get_path(&self.rules)?;
+	fn equals_law_impl_rule(&self, src_id : &CellID, evidence_id : &CellID) -> Result<Cell, SRLError> {
+		let src_path = self.get_path(&src_id)?;
+		let evidence_path = self.get_path(&evidence_id)?;
 
 		// check whether evidence_id is the condition of a case-cell
 		if evidence_id.get_indices().last() != Some(&(0 as usize)) {
-			return Err(SRLError("equals_law_impl".to_string(), "evidence_id can't be condition of case-cell".to_string()));
+			return Err(LawError::create(Law::EqualsLawImpl, LawErrorKind::NotCaseCondition, "evidence_id can't be condition of case-cell").with_path(&evidence_path).into());
 		}
 		if let Cell::Case{..} = evidence_path.get_parent()?.get_cell() {} else {
-			return Err(SRLError("equals_law_impl".to_string(), "evidence_id can't be condition of case-cell (2)".to_string()));
+			return Err(LawError::create(Law::EqualsLawImpl, LawErrorKind::NotCaseCondition, "evidence_id can't be condition of case-cell (2)").with_path(&evidence_path).into());
 		}
 
 		let rule_id = src_id.get_rule_id();
 		if rule_id != evidence_id.get_rule_id() {
-			return Err(SRLError("equals_law_impl".to_string(), "src_id and evidence_id are not in the same rule".to_string()));
+			return Err(LawError::create(Law::EqualsLawImpl, LawErrorKind::DifferentRules, "src_id and evidence_id are not in the same rule").into());
 		}
 		let wrapper = match evidence_path.get_parent()?.get_child(1)?.get_wrapper() {
 			Some(x) => x,
-			None => return Err(SRLError("equals_law_impl".to_string(), "no wrapper!".to_string()))
+			None => return Err(LawError::create(Law::EqualsLawImpl, LawErrorKind::NoWrapper, "no wrapper!").with_path(&evidence_path).into())
 		};
 		if !wrapper.is_around(&src_path) {
-			return Err(SRLError("equals_law_impl".to_string(), "evi-wrapper is not around src_id".to_string()));
+			return Err(LawError::create(Law::EqualsLawImpl, LawErrorKind::NotInWrapper, "evi-wrapper is not around src_id").with_path(&src_path).into());
 		}
 
 		let evi_cell = evidence_path.get_cell();
@@ -85,52 +229,52 @@ impl Database {
 		} else if b.matches(&src_cell) {
 			new = a;
 		} else {
-			return Err(SRLError("equals_law".to_string(), "replace cell does not occur in evidence".to_string()));
+			return Err(LawError::create(Law::EqualsLawImpl, LawErrorKind::NoMatch, "replace cell does not occur in evidence").with_path(&src_path).with_cells(evi_cell.clone(), src_cell.clone()).into());
 		}
 
-		let rule = src_path.replace_by(new);
-		self.add_rule(rule)
+		let rule = src_path.try_replace_by(new)?;
+		Ok(rule)
 	}
 
 	// id: `<(= 'ok' 'wow')>`
-	pub fn inequal_constants(&mut self, id : CellID) -> Result<Cell, SRLError> {
-		let path = id.get_path(&self.rules)?;
+	fn inequal_constants_rule(&self, id : &CellID) -> Result<Cell, SRLError> {
+		let path = self.get_path(&id)?;
 
 		let cell = path.get_cell();
 		let (x, y) = cell.get_equals_cell_arguments()?;
 		if !x.is_constant() {
-			return Err(SRLError("inequals_constants".to_string(), "first arg not constant".to_string()));
+			return Err(LawError::create(Law::InequalConstants, LawErrorKind::NotConstant, "first arg not constant").with_path(&path).into());
 		}
 		if !y.is_constant() {
-			return Err(SRLError("inequals_constants".to_string(), "second arg is not constant".to_string()));
+			return Err(LawError::create(Law::InequalConstants, LawErrorKind::NotConstant, "second arg is not constant").with_path(&path).into());
 		}
 		if x == y {
-			return Err(SRLError("inequals_constants".to_string(), "both args equal".to_string()));
+			return Err(LawError::create(Law::InequalConstants, LawErrorKind::EqualConstants, "both args equal").with_path(&path).into());
 		}
-		let rule = path.replace_by(false_cell());
-		self.add_rule(rule)
+		let rule = path.try_replace_by(false_cell())?;
+		Ok(rule)
 	}
 
 	// cell_id: <ok> => (= 'true' <ok>)
-	pub fn add_eqt(&mut self, cell_id : CellID) -> Result<Cell, SRLError> {
-		let cell_path = cell_id.get_path(&self.rules)?;
+	fn add_eqt_rule(&self, cell_id : &CellID) -> Result<Cell, SRLError> {
+		let cell_path = self.get_path(&cell_id)?;
 
 		if !cell_path.is_bool() {
-			return Err(SRLError("add_eqt".to_string(), "cell is not bool".to_string()));
+			return Err(LawError::create(Law::AddEqt, LawErrorKind::NotBool, "cell is not bool").with_path(&cell_path).into());
 		}
 		let cell = cell_path.get_cell();
-		let rule = cell_path.replace_by(equals_cell(true_cell(), cell));
-		self.add_rule(rule)
+		let rule = cell_path.try_replace_by(equals_cell(true_cell(), cell))?;
+		Ok(rule)
 	}
 
 	// cell_id: (= 'true' <ok>) => <ok>
-	pub fn rm_eqt(&mut self, cell_id : CellID) -> Result<Cell, SRLError> {
-		let cell_path = cell_id.get_path(&self.rules)?;
+	fn rm_eqt_rule(&self, cell_id : &CellID) -> Result<Cell, SRLError> {
+		let cell_path = self.get_path(&cell_id)?;
 
 		let cell = cell_path.get_cell();
 
 		if cell_path.get_indices().is_empty() {
-			return Err(SRLError("rm_eqt".to_string(), "cell has no parents".to_string()));
+			return Err(LawError::create(Law::RmEqt, LawErrorKind::NoParent, "cell has no parents").with_path(&cell_path).into());
 		}
 		let parent_path = cell_path.get_parent()?;
 		let parent_cell = parent_path.get_cell();
@@ -138,241 +282,223 @@ impl Database {
 		if let Ok((x, y)) = parent_cell.get_equals_cell_arguments() {
 			a = x; b = y;
 		} else {
-			return Err(SRLError("rm_eqt".to_string(), "not contained in equals cell".to_string()));
+			return Err(LawError::create(Law::RmEqt, LawErrorKind::NotInEquals, "not contained in equals cell").with_path(&parent_path).into());
 		}
 
 		if a != true_cell() {
-			return Err(SRLError("rm_eqt".to_string(), "first cell in equals is not 'true'".to_string()));
+			return Err(LawError::create(Law::RmEqt, LawErrorKind::CellMismatch, "first cell in equals is not 'true'").with_path(&parent_path).with_cells(true_cell(), a.clone()).into());
 		}
 
 		if b != cell {
-			return Err(SRLError("rm_eqt".to_string(), "second cell in equals is not cell_id".to_string()));
+			return Err(LawError::create(Law::RmEqt, LawErrorKind::CellMismatch, "second cell in equals is not cell_id").with_path(&parent_path).with_cells(cell.clone(), b.clone()).into());
 		}
 
-		let rule = parent_path.replace_by(cell);
+		let rule = parent_path.try_replace_by(cell)?;
 		let tmp_cell_path = CellPath::create(rule.clone(), parent_path.get_indices())?;
 		if !tmp_cell_path.is_bool() {
-			return Err(SRLError("rm_eqt".to_string(), "result is no bool-cell".to_string()));
+			return Err(LawError::create(Law::RmEqt, LawErrorKind::NotBool, "result is no bool-cell").with_path(&tmp_cell_path).into());
 		}
 
-		self.add_rule(rule)
+		Ok(rule)
 	}
 
-	pub fn scope_insertion(&mut self, scope_id : CellID, cell : Cell) -> Result<Cell, SRLError> {
-		let scope_path = scope_id.get_path(&self.rules)?;
+	fn scope_insertion_rule(&self, scope_id : &CellID, cell : &Cell) -> Result<Cell, SRLError> {
+		let scope_path = self.get_path(&scope_id)?;
 
 		let (id, body) : (u32, Cell) = match scope_path.get_cell() {
 			Cell::Scope { id : x, body : y } => (x, *y),
-			_ => return Err(SRLError("scope_insertion".to_string(), "scope_id does not represent scope".to_string()))
+			_ => return Err(LawError::create(Law::ScopeInsertion, LawErrorKind::WrongCellType { expected : CellType::Scope }, "scope_id does not represent scope").with_path(&scope_path).into())
 		};
 		let child_path = scope_path.get_child(0)?;
 		if !child_path.is_complete_bool() {
-			return Err(SRLError("scope_insertion".to_string(), "body is no complete bool cell".to_string()));
+			return Err(LawError::create(Law::ScopeInsertion, LawErrorKind::NotCompleteBool, "body is no complete bool cell").with_path(&child_path).into());
 		}
 		let wrapper = match scope_path.get_wrapper() {
 			Some(x) => x,
-			None => return Err(SRLError("scope_insertion".to_string(), "no wrapper".to_string()))
+			None => return Err(LawError::create(Law::ScopeInsertion, LawErrorKind::NoWrapper, "no wrapper").with_path(&scope_path).into())
 		};
 		if !wrapper.is_positive() {
-			return Err(SRLError("scope_insertion".to_string(), "wrapper is not positive".to_string()));
+			return Err(LawError::create(Law::ScopeInsertion, LawErrorKind::WrapperNotPositive, "wrapper is not positive").with_path(&scope_path).into());
 		}
 
 		let mut highest_id : i32 = scope_path.get_root_cell().get_next_id() as i32 - 1;
 		let norm = cell.get_normalized()?;
 		let id_amount : i32 = norm.get_next_id() as i32;
 
-		let mut path = CellPath::create(body.clone(), vec![])?;
+		// the vars are leaves, so replacing one does not move the others
+		let var_paths : Vec<Vec<usize>> = body.pre_order()
+			.filter(|&(x, _)| *x == var(id))
+			.map(|(_, indices)| indices)
+			.collect();
 
-		loop {
-			loop {
-				match path.get_child(0) {
-					Ok(x) => {
-						path = x;
-					},
-					Err(_) => { break; }
-				}
-			}
-			if let Cell::Var { id : id_out } = path.get_cell() {
-				if id_out == id {
-					let normalized = cell.get_normalized_from((highest_id + 1) as u32)?;
-					let replaced = path.replace_by(normalized);
-					path = CellPath::create(replaced, path.get_indices())?;
-					highest_id += id_amount;
-				}
-			}
-			loop {
-				if path.get_indices().is_empty() {
-					let rule = scope_path.replace_by(path.get_root_cell());
-					return self.add_rule(rule);
-				} else {
-					match path.get_right_sibling() {
-						Ok(x) => {
-							path = x;
-							break;
-						},
-						Err(_) => { path = path.get_parent().unwrap(); }
-					}
-				}
-			}
+		let mut new_body = body.clone();
+		for indices in var_paths {
+			let normalized = cell.get_normalized_from((highest_id + 1) as u32)?;
+			new_body.replace_subcell(&indices, normalized)?;
+			highest_id += id_amount;
 		}
+
+		let rule = scope_path.try_replace_by(new_body)?;
+		Ok(rule)
 	}
 
 	// = 'false' (= 'true' x).
 	//                    <x>   => indices = vec![vec![2]] // indices relative to the scope_id
 	//          <(= 'true' x)>  => scope_id
-	pub fn scope_creation(&mut self, scope_id : CellID, indices : Vec<Vec<usize>>) -> Result<Cell, SRLError> {
-		let mut scope_path = scope_id.get_path(&self.rules)?;
+	fn scope_creation_rule(&self, scope_id : &CellID, indices : &[Vec<usize>]) -> Result<Cell, SRLError> {
+		let mut scope_path = self.get_path(&scope_id)?;
 
 		if !scope_path.is_complete_bool() {
-			return Err(SRLError("scope_creation".to_string(), "scope_id does not contain a complete bool-cell".to_string()));
+			return Err(LawError::create(Law::ScopeCreation, LawErrorKind::NotCompleteBool, "scope_id does not contain a complete bool-cell").with_path(&scope_path).into());
 		}
 
 		let wrapper = match scope_path.get_wrapper() {
 			Some(x) => x,
-			None => return Err(SRLError("scope_creation".to_string(), "no wrapper".to_string()))
+			None => return Err(LawError::create(Law::ScopeCreation, LawErrorKind::NoWrapper, "no wrapper").with_path(&scope_path).into())
 		};
 		if wrapper.is_positive() {
-			return Err(SRLError("scope_creation".to_string(), "wrapper is positive".to_string()));
+			return Err(LawError::create(Law::ScopeCreation, LawErrorKind::WrapperPositive, "wrapper is positive").with_path(&scope_path).into());
 		}
 
 		let cell = scope_path.get_cell();
-		for i in 0..indices.len()-1 {
-			let cell1 = CellPath::create(cell.clone(), indices[i].clone())?.get_cell();
-			let cell2 = CellPath::create(cell.clone(), indices[i+1].clone())?.get_cell(); // optimizable by not calculating everything twice
+		for i in 1..indices.len() {
+			let cell1 = CellPath::create(cell.clone(), indices[i-1].clone())?.get_cell();
+			let cell2 = CellPath::create(cell.clone(), indices[i].clone())?.get_cell(); // optimizable by not calculating everything twice
 			if !cell1.matches(&cell2) {
-				return Err(SRLError("scope_creation".to_string(), "indices do not represent the same cells".to_string()));
+				return Err(LawError::create(Law::ScopeCreation, LawErrorKind::CellMismatch, "indices do not represent the same cells").with_path(&scope_path).with_cells(cell1.clone(), cell2.clone()).into());
 			}
 		}
 
 		let new_id : u32 = scope_path.get_root_cell().get_next_id() as u32;
-		let replaced = scope_path.replace_by(scope(new_id, cell));
+		let replaced = scope_path.try_replace_by(scope(new_id, cell))?;
 		scope_path = CellPath::create(replaced, scope_path.get_indices())?.get_child(0)?;
 
-		for mut index in indices {
+		for index in indices {
 			let mut correct_index = scope_path.get_indices();
-			correct_index.append(&mut index);
+			correct_index.extend_from_slice(index);
 
 			let tmp_path = CellPath::create(scope_path.get_root_cell(), correct_index)?;
-			let new_cell = tmp_path.replace_by(var(new_id));
+			let new_cell = tmp_path.try_replace_by(var(new_id))?;
 			scope_path = CellPath::create(new_cell, scope_path.get_indices())?;
 		}
 
-		self.add_rule(scope_path.get_root_cell())
+		Ok(scope_path.get_root_cell())
 	}
 
-	pub fn implications_derivation(&mut self, case_id : CellID, case_negation_id : CellID) -> Result<Cell, SRLError> {
-		let case_path = case_id.get_path(&self.rules)?;
-		let case_negation_path = case_negation_id.get_path(&self.rules)?;
+	fn implications_derivation_rule(&self, case_id : &CellID, case_negation_id : &CellID) -> Result<Cell, SRLError> {
+		let case_path = self.get_path(&case_id)?;
+		let case_negation_path = self.get_path(&case_negation_id)?;
 
 		let case_cell = case_path.get_cell();
 		let case_negation_cell = case_negation_path.get_cell();
 
 		let (case_condition, case_conclusion) = match case_cell {
 			Cell::Case { condition : x, conclusion : y} => (*x, *y),
-			_ => return Err(SRLError("implications_derivation".to_string(), "case_id does not represent case-cell".to_string()))
+			_ => return Err(LawError::create(Law::ImplicationsDerivation, LawErrorKind::WrongCellType { expected : CellType::Case }, "case_id does not represent case-cell").with_path(&case_path).into())
 		};
 		let (case_negation_condition, case_negation_conclusion) = match case_negation_cell {
 			Cell::Case { condition : x, conclusion : y} => (*x, *y),
-			_ => return Err(SRLError("implications_derivation".to_string(), "case_negation_id does not represent case-cell".to_string()))
+			_ => return Err(LawError::create(Law::ImplicationsDerivation, LawErrorKind::WrongCellType { expected : CellType::Case }, "case_negation_id does not represent case-cell").with_path(&case_negation_path).into())
 		};
 
 		if case_conclusion != case_negation_conclusion {
-			return Err(SRLError("implications_derivation".to_string(), "conclusions differ".to_string()));
+			return Err(LawError::create(Law::ImplicationsDerivation, LawErrorKind::CellMismatch, "conclusions differ").with_path(&case_negation_path).with_cells(case_conclusion.clone(), case_negation_conclusion.clone()).into());
 		}
 
-		if equals_cell(false_cell(), case_condition) != case_negation_condition {
-			return Err(SRLError("implications_derivation".to_string(), "conditions are not correct".to_string()));
+		let expected_condition = equals_cell(false_cell(), case_condition);
+		if expected_condition != case_negation_condition {
+			return Err(LawError::create(Law::ImplicationsDerivation, LawErrorKind::CellMismatch, "conditions are not correct").with_path(&case_negation_path).with_cells(expected_condition, case_negation_condition).into());
 		}
 
 		let case_wrapper = match case_path.get_wrapper() {
 			Some(x) => x,
-			None => return Err(SRLError("implications_derivation".to_string(), "no wrapper (1)".to_string()))
+			None => return Err(LawError::create(Law::ImplicationsDerivation, LawErrorKind::NoWrapper, "no wrapper (1)").with_path(&case_path).into())
 		};
 		let case_negation_wrapper = match case_negation_path.get_wrapper() {
 			Some(x) => x,
-			None => return Err(SRLError("implications_derivation".to_string(), "no wrapper (2)".to_string()))
+			None => return Err(LawError::create(Law::ImplicationsDerivation, LawErrorKind::NoWrapper, "no wrapper (2)").with_path(&case_negation_path).into())
 		};
 		if case_wrapper != case_negation_wrapper {
-			return Err(SRLError("implications_derivation".to_string(), "different wrappers".to_string()));
+			return Err(LawError::create(Law::ImplicationsDerivation, LawErrorKind::DifferentWrappers, "different wrappers").with_path(&case_negation_path).into());
 		}
 
 		if !case_wrapper.is_nexq() {
-			return Err(SRLError("implications_derivation".to_string(), "wrapper contains existance quantor".to_string()));
+			return Err(LawError::create(Law::ImplicationsDerivation, LawErrorKind::WrapperNotNexq, "wrapper contains existance quantor").with_path(&case_path).into());
 		}
 
 		if !case_wrapper.is_positive() {
-			return Err(SRLError("implications_derivation".to_string(), "wrapper is negative".to_string()));
+			return Err(LawError::create(Law::ImplicationsDerivation, LawErrorKind::WrapperNotPositive, "wrapper is negative").with_path(&case_path).into());
 		}
-		self.add_rule(case_conclusion)
+		Ok(case_conclusion)
 	}
 
-	pub fn scope_exchange(&mut self, outer_scope_id : CellID) -> Result<Cell, SRLError> {
-		let outer_scope_path = outer_scope_id.get_path(&self.rules)?;
+	fn scope_exchange_rule(&self, outer_scope_id : &CellID) -> Result<Cell, SRLError> {
+		let outer_scope_path = self.get_path(&outer_scope_id)?;
 
 		let inner_scope_path = outer_scope_path.get_child(0)?;
 		let outer_id = match outer_scope_path.get_cell() {
 			Cell::Scope { id : x, ..} => x,
-			_ => return Err(SRLError("scope_exchange".to_string(), "outer cell is no scope".to_string()))
+			_ => return Err(LawError::create(Law::ScopeExchange, LawErrorKind::WrongCellType { expected : CellType::Scope }, "outer cell is no scope").with_path(&outer_scope_path).into())
 		};
 		let (inner_id, body) = match inner_scope_path.get_cell() {
 			Cell::Scope { id : x, body : y } => (x, *y),
-			_ => return Err(SRLError("scope_exchange".to_string(), "inner cell is no scope".to_string()))
+			_ => return Err(LawError::create(Law::ScopeExchange, LawErrorKind::WrongCellType { expected : CellType::Scope }, "inner cell is no scope").with_path(&inner_scope_path).into())
 		};
 
-		let rule = outer_scope_path.replace_by(scope(inner_id, scope(outer_id, body)));
-		self.add_rule(rule)
+		let rule = outer_scope_path.try_replace_by(scope(inner_id, scope(outer_id, body)))?;
+		Ok(rule)
 	}
 
-	pub fn case_creation(&mut self, cell_id : CellID, arg_cell : Cell) -> Result<Cell, SRLError> {
-		let path = cell_id.get_path(&self.rules)?;
+	fn case_creation_rule(&self, cell_id : &CellID, arg_cell : &Cell) -> Result<Cell, SRLError> {
+		let path = self.get_path(&cell_id)?;
 		let wrapper = match path.get_wrapper() {
 			Some(x) => x,
-			None => return Err(SRLError("case_creation".to_string(), "no wrapper".to_string()))
+			None => return Err(LawError::create(Law::CaseCreation, LawErrorKind::NoWrapper, "no wrapper").with_path(&path).into())
 		};
 		if !wrapper.is_positive() {
-			return Err(SRLError("case_creation".to_string(), "wrapper is not positive".to_string()));
+			return Err(LawError::create(Law::CaseCreation, LawErrorKind::WrapperNotPositive, "wrapper is not positive").with_path(&path).into());
 		}
 		let cell = path.get_cell();
-		let rule = path.replace_by(case(arg_cell, cell));
-		self.add_rule(rule)
+		let rule = path.try_replace_by(case(arg_cell.clone(), cell))?;
+		Ok(rule)
 	}
 
 	// <(= 'false' {0 (= 'false' (p 0 1))})>
-	pub fn declaration(&mut self, cell_id : CellID, string : &str) -> Result<Cell, SRLError> {
+	fn declaration_rule(&self, cell_id : &CellID, string : &str) -> Result<Cell, SRLError> {
 		// occurence checks
 		if self.contains_cellname(string) {
-			return Err(SRLError("declaration".to_string(), "string does already occur".to_string()));
+			return Err(LawError::create(Law::Declaration, LawErrorKind::NameOccurs, "string does already occur").into());
 		}
 
 		// wrapper checks
-		let cell_path = cell_id.get_path(&self.rules)?;
+		let cell_path = self.get_path(&cell_id)?;
 		let wrapper = match cell_path.get_wrapper() {
 			Some(x) => x,
-			None => return Err(SRLError("declaration".to_string(), "no wrapper".to_string()))
+			None => return Err(LawError::create(Law::Declaration, LawErrorKind::NoWrapper, "no wrapper").with_path(&cell_path).into())
 		};
 		if !wrapper.is_positive() {
-			return Err(SRLError("declaration".to_string(), "wrapper is negative".to_string()));
+			return Err(LawError::create(Law::Declaration, LawErrorKind::WrapperNotPositive, "wrapper is negative").with_path(&cell_path).into());
 		}
 		if !wrapper.is_nallq() {
-			return Err(SRLError("declaration".to_string(), "wrapper contains all quantor".to_string()));
+			return Err(LawError::create(Law::Declaration, LawErrorKind::WrapperNotNallq, "wrapper contains all quantor").with_path(&cell_path).into());
 		}
 
 		// check (= 'false' {0 (= 'false' * )}) pattern
 		let (x, y) = cell_path.get_cell().get_equals_cell_arguments()?;
 		if x != false_cell() {
-			return Err(SRLError("declaration".to_string(), "first arg of equals cell is not 'false'".to_string()));
+			return Err(LawError::create(Law::Declaration, LawErrorKind::CellMismatch, "first arg of equals cell is not 'false'").with_path(&cell_path).with_cells(false_cell(), x.clone()).into());
 		}
 		let (id, body) = match y {
 			Cell::Scope { id : x, body : y } => (x, y),
-			_ => return Err(SRLError("declaration".to_string(), "second arg is no scope".to_string()))
+			_ => return Err(LawError::create(Law::Declaration, LawErrorKind::WrongCellType { expected : CellType::Scope }, "second arg is no scope").with_path(&cell_path).into())
 		};
 		let (a, b) = body.get_equals_cell_arguments()?;
 		if a != false_cell() {
-			return Err(SRLError("declaration".to_string(), "scope does not contain (= 'false' *)".to_string()));
+			return Err(LawError::create(Law::Declaration, LawErrorKind::CellMismatch, "scope does not contain (= 'false' *)").with_path(&cell_path).with_cells(false_cell(), a.clone()).into());
 		}
 
 		let new = b.replace_all(var(id), try_simple(string.to_string())?);
-		let rule = cell_path.replace_by(new);
-		self.add_rule(rule)
+		let rule = cell_path.try_replace_by(new)?;
+		Ok(rule)
 	}
 }
@@ -3,6 +3,7 @@ use cell::Cell;
 use gen::*;
 use misc::index_in_len;
 
+#[derive(Clone)]
 pub struct Wrapper {
 	cell_path : CellPath,
 	positive : bool,
@@ -24,20 +25,17 @@ impl PartialEq for Wrapper {
 
 impl CellPath {
 	pub fn get_wrapper(&self) -> Option<Wrapper> {
-		let mut indices = self.get_indices();
 		let mut positive : bool = true;
 		let mut nallq : bool = true;
 		let mut nexq : bool = true;
-		let mut cell : Cell = self.get_root_cell();
+		let mut cell = self.get_root_ref();
 
-		while !indices.is_empty() {
-			let index : usize = indices.remove(0);
+		for index in self.get_indices() {
 			match cell {
-				Cell::Scope {..} => {
+				&Cell::Scope {..} => {
 					if index != 0 {
 						return None
 					}
-					cell = cell.get_subcell(0);
 
 					if positive {
 						nallq = false;
@@ -45,18 +43,17 @@ impl CellPath {
 						nexq = false;
 					}
 				},
-				Cell::Case {..} => {
+				&Cell::Case {..} => {
 					if index != 1 { // only the second arg shall be 'in wrapper'
 						return None
 					}
+					continue; // the walk does not descend into case cells, the remaining indices have to be 1 as well
 				},
-				Cell::Complex {..} => { // only = 'false' is allowed here!
-					let (x, y) = match cell.get_equals_cell_arguments() {
-						Ok((x, y)) => (x, y),
-						_ => return None
-					};
-					if index == 2  && x == false_cell() {
-						cell = y;
+				&Cell::Complex { cells : ref cells_out } => { // only = 'false' is allowed here!
+					if cells_out.len() != 3 || cells_out[0] != simple_by_str("=") {
+						return None
+					}
+					if index == 2 && cells_out[1] == false_cell() {
 						positive = !positive;
 					} else {
 						return None
@@ -64,6 +61,10 @@ impl CellPath {
 				},
 				_ => return None
 			}
+			cell = match cell.get_subcell_ref(index) {
+				Some(x) => x,
+				None => return None
+			};
 		}
 		Some(Wrapper {cell_path : self.clone(), positive : positive, nallq : nallq, nexq : nexq})
 	}
@@ -73,6 +74,7 @@ impl Wrapper {
 	pub fn is_positive(&self) -> bool { self.positive }
 	pub fn is_nallq(&self) -> bool { self.nallq }
 	pub fn is_nexq(&self) -> bool { self.nexq }
+	pub fn get_cell_path(&self) -> CellPath { self.cell_path.clone() }
 
 	pub fn is_around(&self, path : &CellPath) -> bool {
 		let indices1 = self.cell_path.get_indices();
@@ -121,3 +123,13 @@ fn test_get_wrapper() { // XXX pretty bad test
 		assert!(false);
 	}
 }
+
+#[test]
+fn test_get_wrapper_in_case() {
+	// [=> c (= 'false' x)]
+	let cell = case(simple_by_str("c"), equals_cell(false_cell(), simple_by_str("x")));
+	assert!(CellPath::create(cell.clone(), vec![1]).unwrap().get_wrapper().unwrap().is_positive());
+	assert!(CellPath::create(cell.clone(), vec![0]).unwrap().get_wrapper().is_none());
+	// the conclusion is not entered, so its (= 'false' *) does not count
+	assert!(CellPath::create(cell.clone(), vec![1, 2]).unwrap().get_wrapper().is_none());
+}
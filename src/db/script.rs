@@ -0,0 +1,158 @@
+use std::fmt;
+use std::collections::HashMap;
+use cell::Cell;
+use error::SRLError;
+use navi::{CellID, RuleHandle};
+use super::Database;
+use super::proof::LawApplication;
+
+// a proof script has one law application per line, optionally followed by the expected resulting rule:
+//   // comment
+//   add_eqt #1/0 -> {0 (= 'true' wow)}.
+//   scope_insertion #3 (a b)
+//   equals_law #4/0.1 #1
+// a proof log (see proof.rs) is a valid proof script: the handle of the result CellID in front of the rule
+// stands for the rule the step derives in the replay, later inputs with that handle are redirected to it
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct ScriptStep {
+	line : usize,
+	application : LawApplication,
+	result : Option<CellID>, // as logged
+	expected : Option<Cell>
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct ProofScript {
+	steps : Vec<ScriptStep>
+}
+
+// a step whose result differs from the expected rule
+#[derive(Clone, PartialEq, Debug)]
+pub struct Mismatch {
+	line : usize,
+	expected : Cell,
+	actual : Cell
+}
+
+pub struct ReplayReport {
+	applied : usize,
+	failure : Option<(usize, SRLError)>, // line and error of the first failing step, the replay stops there
+	mismatches : Vec<Mismatch>
+}
+
+impl ScriptStep {
+	pub fn get_line(&self) -> usize { self.line }
+	pub fn get_application(&self) -> &LawApplication { &self.application }
+	pub fn get_result(&self) -> Option<&CellID> { self.result.as_ref() }
+	pub fn get_expected(&self) -> Option<Cell> { self.expected.clone() }
+}
+
+impl Mismatch {
+	pub fn get_line(&self) -> usize { self.line }
+	pub fn get_expected(&self) -> Cell { self.expected.clone() }
+	pub fn get_actual(&self) -> Cell { self.actual.clone() }
+}
+
+impl fmt::Display for Mismatch {
+	fn fmt(&self, f : &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "line {}: expected '{}', got '{}'", self.line, self.expected.to_rule_string(), self.actual.to_rule_string())
+	}
+}
+
+impl ReplayReport {
+	pub fn count_applied(&self) -> usize { self.applied }
+	pub fn get_failure(&self) -> Option<&(usize, SRLError)> { self.failure.as_ref() }
+	pub fn get_mismatches(&self) -> &[Mismatch] { &self.mismatches }
+
+	pub fn is_success(&self) -> bool {
+		self.failure.is_none() && self.mismatches.is_empty()
+	}
+}
+
+// [<result CellID>] <rule>
+fn parse_expected(string : &str) -> Result<(Option<CellID>, Cell), SRLError> {
+	let mut string = string.trim();
+	let mut result = None;
+	if string.starts_with('#') {
+		let end = string.find(' ').unwrap_or(string.len());
+		result = Some(string[..end].parse()?);
+		string = string[end..].trim();
+	}
+	if !string.ends_with('.') {
+		return Err(SRLError::parse("ProofScript::parse", "expected rule does not end with '.'"));
+	}
+	Ok((result, Cell::by_string(&string[..string.len()-1])?))
+}
+
+fn parse_step(line : &str, line_number : usize) -> Result<ScriptStep, SRLError> {
+	let (application, result, expected) = match line.find(" -> ") {
+		Some(x) => {
+			let (result, expected) = parse_expected(&line[x+4..])?;
+			(&line[..x], result, Some(expected))
+		},
+		None => (line, None, None)
+	};
+	Ok(ScriptStep { line : line_number, application : application.parse()?, result : result, expected : expected })
+}
+
+impl ProofScript {
+	pub fn parse(string : &str) -> Result<ProofScript, SRLError> {
+		let mut steps = Vec::new();
+		for (i, line) in string.lines().enumerate() {
+			let line = line.trim();
+			if line.is_empty() || line.starts_with("//") {
+				continue;
+			}
+			match parse_step(line, i + 1) {
+				Ok(x) => steps.push(x),
+				Err(srl_error) => return Err(SRLError::parse("ProofScript::parse", &format!("line {}: {}", i + 1, srl_error)))
+			}
+		}
+		Ok(ProofScript { steps : steps })
+	}
+
+	pub fn get_steps(&self) -> &[ScriptStep] { &self.steps }
+
+	// applies the steps in order until one fails
+	pub fn replay(&self, db : &mut Database) -> ReplayReport {
+		let mut report = ReplayReport { applied : 0, failure : None, mismatches : Vec::new() };
+		let mut handles : HashMap<RuleHandle, RuleHandle> = HashMap::new(); // logged result -> result of the replay
+		for step in &self.steps {
+			let inputs = step.application.get_inputs().into_iter()
+				.map(|id| match id.get_handle().and_then(|x| handles.get(&x)) {
+					Some(&handle) => id.with_handle(handle),
+					None => id
+				})
+				.collect();
+			let application = step.application.with_inputs(inputs);
+			let (actual, handle) = match db.derive(&application).and_then(|x| Ok((db.get_rule(x), db.get_rule_handle(x)?))) {
+				Ok(x) => x,
+				Err(srl_error) => {
+					report.failure = Some((step.line, srl_error));
+					break;
+				}
+			};
+			if let Some(logged) = step.result.as_ref().and_then(|x| x.get_handle()) {
+				handles.insert(logged, handle);
+			}
+			report.applied += 1;
+			if let Some(ref expected) = step.expected {
+				let equal = match expected.get_normalized() {
+					Ok(x) => x == actual,
+					Err(_) => false
+				};
+				if !equal {
+					report.mismatches.push(Mismatch { line : step.line, expected : expected.clone(), actual : actual });
+				}
+			}
+		}
+		report
+	}
+}
+
+impl Database {
+	pub fn replay(&mut self, script : &ProofScript) -> ReplayReport {
+		script.replay(self)
+	}
+}
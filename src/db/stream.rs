@@ -0,0 +1,189 @@
+use std::fmt;
+use std::io::{BufRead, BufReader, Read};
+use cell::Cell;
+use error::SRLError;
+use super::{DERIVED_MARKER, GOAL_MARKER};
+
+// position of the first char of a rule in its source, both starting at 1
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct SourcePosition {
+	line : usize,
+	column : usize
+}
+
+impl SourcePosition {
+	pub fn get_line(&self) -> usize { self.line }
+	pub fn get_column(&self) -> usize { self.column }
+}
+
+impl fmt::Display for SourcePosition {
+	fn fmt(&self, f : &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "{}:{}", self.line, self.column)
+	}
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum Section { Source, Derived, Goal }
+
+pub struct SourceRule {
+	cell : Cell,
+	position : SourcePosition,
+	section : Section
+}
+
+impl SourceRule {
+	pub fn get_cell(&self) -> Cell { self.cell.clone() }
+	pub fn into_cell(self) -> Cell { self.cell }
+	pub fn get_position(&self) -> SourcePosition { self.position }
+	// whether the rule comes after DERIVED_MARKER
+	pub fn is_derived(&self) -> bool { self.section == Section::Derived }
+	// whether the rule comes after GOAL_MARKER, it is no rule then but a goal
+	pub fn is_goal(&self) -> bool { self.section == Section::Goal }
+}
+
+// reads rules one by one, without holding more than the current rule in memory
+// parse errors are yielded and reading continues behind the broken rule, io errors end the iteration
+pub struct RuleReader<R : Read> {
+	reader : BufReader<R>,
+	state : State
+}
+
+struct State {
+	pending : String, // text of the current rule
+	line_start : usize, // start of the current line in pending
+	start : Option<SourcePosition>, // position of the first non-whitespace char in pending
+	line : usize,
+	column : usize,
+	section : Section,
+	seen : Vec<Section>, // the sections, that have been started by a marker
+	done : bool
+}
+
+impl<R : Read> RuleReader<R> {
+	pub fn new(reader : R) -> RuleReader<R> {
+		let state = State { pending : String::new(), line_start : 0, start : None, line : 1, column : 1, section : Section::Source, seen : Vec::new(), done : false };
+		RuleReader { reader : BufReader::new(reader), state : state }
+	}
+}
+
+impl State {
+	fn error(&self, position : SourcePosition, message : &str) -> SRLError {
+		SRLError::parse("RuleReader::next", &format!("{}: {}", position, message))
+	}
+
+	// called at the end of every line, line is the number of the line that ended
+	fn check_marker(&mut self, line : usize) -> Option<Result<SourceRule, SRLError>> {
+		let (section, name) = match self.pending[self.line_start..].trim() {
+			x if x == DERIVED_MARKER => (Section::Derived, "derived"),
+			x if x == GOAL_MARKER => (Section::Goal, "goal"),
+			_ => {
+				self.line_start = self.pending.len();
+				return None;
+			}
+		};
+		let position = SourcePosition { line : line, column : 1 };
+		let result = if !self.pending[..self.line_start].trim().is_empty() {
+			Some(Err(self.error(position, &format!("{} marker inside of a rule", name))))
+		} else if self.seen.contains(&section) {
+			Some(Err(self.error(position, &format!("multiple {} sections", name))))
+		} else {
+			None
+		};
+		self.section = section;
+		self.seen.push(section);
+		self.pending.clear();
+		self.line_start = 0;
+		self.start = None;
+		result
+	}
+
+	fn finish_rule(&mut self) -> Result<SourceRule, SRLError> {
+		let position = self.start.unwrap_or(SourcePosition { line : self.line, column : self.column });
+		let text = ::std::mem::replace(&mut self.pending, String::new());
+		self.line_start = 0;
+		self.start = None;
+
+		if text.trim().is_empty() {
+			return Err(self.error(position, "empty rule"));
+		}
+		match Cell::by_string(&text).and_then(|x| x.get_normalized()) {
+			Ok(cell) => Ok(SourceRule { cell : cell, position : position, section : self.section }),
+			Err(srl_error) => Err(SRLError::context("RuleReader::next", &format!("{}: invalid rule", position), srl_error))
+		}
+	}
+
+	// processes one byte, returns a rule if one is complete
+	fn feed(&mut self, byte : u8) -> Option<Result<SourceRule, SRLError>> {
+		let position = SourcePosition { line : self.line, column : self.column };
+		if byte == b'\n' {
+			self.line += 1;
+			self.column = 1;
+		} else {
+			self.column += 1;
+		}
+
+		if byte == b'.' {
+			return Some(self.finish_rule());
+		}
+		if byte == b'\n' {
+			self.pending.push(' ');
+			return self.check_marker(position.line);
+		}
+		if self.start.is_none() && !(byte as char).is_whitespace() {
+			self.start = Some(position);
+		}
+		self.pending.push(byte as char); // non-ascii bytes are rejected by Cell::by_string anyway
+		None
+	}
+
+	// called at the end of the input
+	fn finish(&mut self) -> Option<Result<SourceRule, SRLError>> {
+		self.done = true;
+		let line = self.line;
+		if let Some(result) = self.check_marker(line) {
+			return Some(result);
+		}
+		if self.pending.trim().is_empty() {
+			return None;
+		}
+		let position = self.start.unwrap_or(SourcePosition { line : self.line, column : self.column });
+		self.pending.clear();
+		Some(Err(self.error(position, "rule does not end with '.'")))
+	}
+}
+
+impl<R : Read> Iterator for RuleReader<R> {
+	type Item = Result<SourceRule, SRLError>;
+
+	fn next(&mut self) -> Option<Result<SourceRule, SRLError>> {
+		while !self.state.done {
+			let (result, consumed) = {
+				let buffer = match self.reader.fill_buf() {
+					Ok(x) => x,
+					Err(io_error) => {
+						self.state.done = true;
+						return Some(Err(SRLError::io("RuleReader::next", "failed to read", io_error)));
+					}
+				};
+				if buffer.is_empty() {
+					return self.state.finish();
+				}
+				let mut result = None;
+				let mut consumed = 0;
+				for &byte in buffer {
+					consumed += 1;
+					result = self.state.feed(byte);
+					if result.is_some() {
+						break;
+					}
+				}
+				(result, consumed)
+			};
+			self.reader.consume(consumed);
+			if result.is_some() {
+				return result;
+			}
+		}
+		None
+	}
+}
@@ -1,6 +1,258 @@
 use std::fmt;
+use std::io;
+use std::error::Error;
+use cell::{Cell, CellType};
+use navi::CellPath;
+use law::Law;
 
-pub struct SRLError(pub String /* problem-src-procedure */ , pub String /* errordescription */);
+// Display of every variant is "<procedure>: <description>"
+pub enum SRLError {
+	Parse { procedure : String, message : String },
+	Navigation { procedure : String, kind : NavigationErrorKind, path : Option<CellPath> },
+	InvalidCell { procedure : String, kind : CellErrorKind },
+	Normalization { procedure : String, kind : NormalizationErrorKind },
+	Database { procedure : String, kind : DatabaseErrorKind },
+	Law(Box<LawError>), // boxed, so Result<_, SRLError> stays small
+	Io { procedure : String, message : String, source : io::Error },
+	Context { procedure : String, message : String, source : Box<SRLError> } // source happened while doing what message says
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub enum NavigationErrorKind {
+	NoParent,
+	NoLeftSibling,
+	NoRightSibling,
+	InvalidIndex,
+	RuleOutOfRange,
+	QueryNoMatch,
+	QueryAmbiguous { count : usize }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub enum CellErrorKind {
+	EmptySimpleString,
+	InvalidChar,
+	InvalidSimpleString,
+	ShortComplex,
+	NotComplex,
+	EqualsArgumentCount,
+	NoEqualsSymbol
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub enum NormalizationErrorKind {
+	VarOutOfScope { id : u32 },
+	VarNotInScope { id : u32, scope_ids : Vec<u32> },
+	DuplicateScopeId { id : u32 }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub enum DatabaseErrorKind {
+	WriteProtected,
+	RuleOutOfRange,
+	UnknownHandle { id : u64 },
+	NoTransaction,
+	UnrelatedBranches,
+	GoalOutOfRange
+}
+
+// the precondition of a law, that was not fulfilled
+#[derive(Clone, PartialEq, Debug)]
+pub enum LawErrorKind {
+	NoWrapper,
+	WrapperPositive,
+	WrapperNotPositive,
+	WrapperNotNexq,
+	WrapperNotNallq,
+	NotInWrapper, // the wrapper is not around the cell
+	DifferentWrappers,
+	DifferentRules,
+	NotCaseCondition,
+	NoMatch, // the cell to replace does not occur in the evidence
+	NotConstant,
+	EqualConstants,
+	NotBool,
+	NotCompleteBool,
+	NoParent,
+	NotInEquals,
+	WrongCellType { expected : CellType },
+	CellMismatch, // see expected and actual
+	NameOccurs,
+	WrongArguments // the inputs or the argument do not fit the law
+}
+
+pub struct LawError {
+	law : Law,
+	kind : LawErrorKind,
+	message : String,
+	path : Option<CellPath>,
+	expected : Option<Cell>,
+	actual : Option<Cell>
+}
+
+impl LawError {
+	pub fn create(law : Law, kind : LawErrorKind, message : &str) -> LawError {
+		LawError { law : law, kind : kind, message : message.to_string(), path : None, expected : None, actual : None }
+	}
+
+	pub fn with_path(mut self, path : &CellPath) -> LawError {
+		self.path = Some(path.clone());
+		self
+	}
+
+	pub fn with_cells(mut self, expected : Cell, actual : Cell) -> LawError {
+		self.expected = Some(expected);
+		self.actual = Some(actual);
+		self
+	}
+
+	pub fn get_law(&self) -> Law { self.law.clone() }
+
+	// the procedure name shown in messages, the names of the former string errors are kept,
+	// even where they differ from Law::get_name
+	pub fn get_procedure(&self) -> &'static str {
+		match (self.law, &self.kind) {
+			(Law::InequalConstants, _) => "inequals_constants",
+			(Law::EqualsLawImpl, &LawErrorKind::NoMatch) => "equals_law",
+			(law, _) => law.get_name()
+		}
+	}
+	pub fn get_kind(&self) -> LawErrorKind { self.kind.clone() }
+	pub fn get_path(&self) -> Option<CellPath> { self.path.clone() }
+	pub fn get_expected(&self) -> Option<Cell> { self.expected.clone() }
+	pub fn get_actual(&self) -> Option<Cell> { self.actual.clone() }
+}
+
+impl From<LawError> for SRLError {
+	fn from(law_error : LawError) -> SRLError {
+		SRLError::Law(Box::new(law_error))
+	}
+}
+
+impl SRLError {
+	pub fn parse(procedure : &str, message : &str) -> SRLError {
+		SRLError::Parse { procedure : procedure.to_string(), message : message.to_string() }
+	}
+
+	pub fn navigation(procedure : &str, kind : NavigationErrorKind) -> SRLError {
+		SRLError::Navigation { procedure : procedure.to_string(), kind : kind, path : None }
+	}
+
+	pub fn navigation_at(procedure : &str, kind : NavigationErrorKind, path : &CellPath) -> SRLError {
+		SRLError::Navigation { procedure : procedure.to_string(), kind : kind, path : Some(path.clone()) }
+	}
+
+	pub fn invalid_cell(procedure : &str, kind : CellErrorKind) -> SRLError {
+		SRLError::InvalidCell { procedure : procedure.to_string(), kind : kind }
+	}
+
+	pub fn normalization(procedure : &str, kind : NormalizationErrorKind) -> SRLError {
+		SRLError::Normalization { procedure : procedure.to_string(), kind : kind }
+	}
+
+	pub fn database(procedure : &str, kind : DatabaseErrorKind) -> SRLError {
+		SRLError::Database { procedure : procedure.to_string(), kind : kind }
+	}
+
+	pub fn io(procedure : &str, message : &str, source : io::Error) -> SRLError {
+		SRLError::Io { procedure : procedure.to_string(), message : message.to_string(), source : source }
+	}
+
+	pub fn context(procedure : &str, message : &str, source : SRLError) -> SRLError {
+		SRLError::Context { procedure : procedure.to_string(), message : message.to_string(), source : Box::new(source) }
+	}
+
+	pub fn get_procedure(&self) -> String {
+		match self {
+			&SRLError::Parse { ref procedure, .. } => procedure.clone(),
+			&SRLError::Navigation { ref procedure, .. } => procedure.clone(),
+			&SRLError::InvalidCell { ref procedure, .. } => procedure.clone(),
+			&SRLError::Normalization { ref procedure, .. } => procedure.clone(),
+			&SRLError::Database { ref procedure, .. } => procedure.clone(),
+			&SRLError::Law(ref law_error) => law_error.get_procedure().to_string(),
+			&SRLError::Io { ref procedure, .. } => procedure.clone(),
+			&SRLError::Context { ref procedure, .. } => procedure.clone()
+		}
+	}
+
+	pub fn get_description(&self) -> String {
+		match self {
+			&SRLError::Parse { ref message, .. } => message.clone(),
+			&SRLError::Navigation { ref kind, .. } => kind.to_string(),
+			&SRLError::InvalidCell { ref kind, .. } => kind.to_string(),
+			&SRLError::Normalization { ref kind, .. } => kind.to_string(),
+			&SRLError::Database { ref kind, .. } => kind.to_string(),
+			&SRLError::Law(ref law_error) => law_error.message.clone(),
+			&SRLError::Io { ref message, .. } => message.clone(),
+			&SRLError::Context { ref message, .. } => message.clone()
+		}
+	}
+
+	pub fn get_law_error(&self) -> Option<&LawError> {
+		match self {
+			&SRLError::Law(ref law_error) => Some(law_error),
+			_ => None
+		}
+	}
+
+	pub fn get_source(&self) -> Option<&SRLError> {
+		match self {
+			&SRLError::Context { ref source, .. } => Some(source),
+			_ => None
+		}
+	}
+}
+
+impl fmt::Display for NavigationErrorKind {
+	fn fmt(&self, f : &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			&NavigationErrorKind::NoParent => f.write_str("no parent"),
+			&NavigationErrorKind::NoLeftSibling => f.write_str("no left sibling"),
+			&NavigationErrorKind::NoRightSibling => f.write_str("no right sibling"),
+			&NavigationErrorKind::InvalidIndex => f.write_str("index is unacceptable!"),
+			&NavigationErrorKind::RuleOutOfRange => f.write_str("index of rule_id out of range"),
+			&NavigationErrorKind::QueryNoMatch => f.write_str("query matches no cell"),
+			&NavigationErrorKind::QueryAmbiguous { count } => write!(f, "query matches {} cells", count)
+		}
+	}
+}
+
+impl fmt::Display for CellErrorKind {
+	fn fmt(&self, f : &mut fmt::Formatter) -> fmt::Result {
+		f.write_str(match self {
+			&CellErrorKind::EmptySimpleString => "string has length 0",
+			&CellErrorKind::InvalidChar => "invalid char",
+			&CellErrorKind::InvalidSimpleString => "weird invalid stuff",
+			&CellErrorKind::ShortComplex => "complex cell needs more than 1 argument",
+			&CellErrorKind::NotComplex => "cell is not complex",
+			&CellErrorKind::EqualsArgumentCount => "complex cell does not have 3 arguments",
+			&CellErrorKind::NoEqualsSymbol => "first cell is not ="
+		})
+	}
+}
+
+impl fmt::Display for NormalizationErrorKind {
+	fn fmt(&self, f : &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			&NormalizationErrorKind::VarOutOfScope { id } => write!(f, "already out of scope with id '{}'", id),
+			&NormalizationErrorKind::VarNotInScope { id, ref scope_ids } => write!(f, "id '{}' is not in scope_ids '{:?}'", id, scope_ids),
+			&NormalizationErrorKind::DuplicateScopeId { id } => write!(f, "id '{}' used twice", id)
+		}
+	}
+}
+
+impl fmt::Display for DatabaseErrorKind {
+	fn fmt(&self, f : &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			&DatabaseErrorKind::WriteProtected => f.write_str("This rule is write protected"),
+			&DatabaseErrorKind::RuleOutOfRange => f.write_str("out of range"),
+			&DatabaseErrorKind::UnknownHandle { id } => write!(f, "rule #{} does not exist (anymore)", id),
+			&DatabaseErrorKind::NoTransaction => f.write_str("no transaction is open"),
+			&DatabaseErrorKind::UnrelatedBranches => f.write_str("the databases are no branches of the same database"),
+			&DatabaseErrorKind::GoalOutOfRange => f.write_str("goal out of range")
+		}
+	}
+}
 
 impl fmt::Debug for SRLError {
 	fn fmt(&self, f : &mut fmt::Formatter) -> fmt::Result {
@@ -14,8 +266,75 @@ impl fmt::Display for SRLError {
 	}
 }
 
+impl fmt::Display for LawError {
+	fn fmt(&self, f : &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "{}: {}", self.get_procedure(), self.message)
+	}
+}
+
+impl fmt::Debug for LawError {
+	fn fmt(&self, f : &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "{}", self)
+	}
+}
+
+impl Error for LawError {}
+
+// a Law error is displayed with the message of its LawError, so the LawError is no source (see get_law_error)
+impl Error for SRLError {
+	fn source(&self) -> Option<&(dyn Error + 'static)> {
+		match self {
+			&SRLError::Io { ref source, .. } => Some(source),
+			&SRLError::Context { ref source, .. } => Some(source.as_ref()),
+			_ => None
+		}
+	}
+}
+
 impl SRLError {
+	// the location is appended, if known: "<procedure>: <description> in <highlighted root>"
 	fn to_string(&self) -> String {
-		return self.0.to_string() + ": " + &self.1;
+		let string = self.get_procedure() + ": " + &self.get_description();
+		match self.get_path() {
+			Some(path) => string + " in " + &path.to_highlighted_string(),
+			None => string
+		}
+	}
+
+	pub fn get_path(&self) -> Option<CellPath> {
+		match self {
+			&SRLError::Navigation { ref path, .. } => path.clone(),
+			&SRLError::Law(ref law_error) => law_error.get_path(),
+			_ => None
+		}
 	}
 }
+
+#[test]
+fn test_display() {
+	use gen::*;
+
+	assert_eq!(SRLError::navigation("CellPath::get_parent", NavigationErrorKind::NoParent).to_string(), "CellPath::get_parent: no parent");
+	assert_eq!(format!("{}", SRLError::normalization("get_normalized_from_r", NormalizationErrorKind::DuplicateScopeId { id : 2 })), "get_normalized_from_r: id '2' used twice");
+
+	let err : SRLError = LawError::create(Law::AddEqt, LawErrorKind::NotBool, "cell is not bool").with_cells(true_cell(), var(0)).into();
+	assert_eq!(format!("{:?}", err), "add_eqt: cell is not bool");
+	assert_eq!(err.get_law_error().unwrap().get_kind(), LawErrorKind::NotBool);
+	assert!(err.source().is_none()); // the LawError has the same message, it is reached by get_law_error
+	assert!(SRLError::parse("x", "y").source().is_none());
+
+	// the messages of the string errors are kept
+	let err : SRLError = LawError::create(Law::InequalConstants, LawErrorKind::EqualConstants, "both args equal").into();
+	assert_eq!(err.to_string(), "inequals_constants: both args equal");
+	let err : SRLError = LawError::create(Law::EqualsLawImpl, LawErrorKind::NoMatch, "replace cell does not occur in evidence").into();
+	assert_eq!(err.to_string(), "equals_law: replace cell does not occur in evidence");
+	assert_eq!(err.get_law_error().unwrap().get_law(), Law::EqualsLawImpl);
+
+	let err = SRLError::io("Database::by_filename", "Cannot open file: 'x'", io::Error::new(io::ErrorKind::NotFound, "nope"));
+	assert_eq!(err.source().unwrap().to_string(), "nope");
+
+	let err = SRLError::context("RuleReader::next", "1:1: invalid rule", SRLError::invalid_cell("Cell::by_string", CellErrorKind::InvalidChar));
+	assert_eq!(err.to_string(), "RuleReader::next: 1:1: invalid rule");
+	assert_eq!(err.source().unwrap().to_string(), "Cell::by_string: invalid char");
+	assert_eq!(err.get_source().unwrap().get_procedure(), "Cell::by_string");
+}
@@ -1,5 +1,5 @@
 use cell::Cell;
-use error::SRLError;
+use error::{SRLError, CellErrorKind};
 use cell::SimpleString;
 
 pub fn true_cell() -> Cell {
@@ -23,18 +23,31 @@ pub fn try_simple_by_str(string_arg : &str) -> Result<Cell, SRLError> {
 }
 
 pub fn simple(string_arg : String) -> Cell {
-	try_simple(string_arg).unwrap()
+	match try_simple(string_arg) {
+		Ok(x) => x,
+		Err(srl_error) => panic!("{}", srl_error)
+	}
 }
 
 pub fn simple_by_str(string_arg : &str) -> Cell {
-	try_simple_by_str(string_arg).unwrap()
+	match try_simple_by_str(string_arg) {
+		Ok(x) => x,
+		Err(srl_error) => panic!("{}", srl_error)
+	}
 }
 
-pub fn complex(cells_arg : Vec<Cell>) -> Cell {
+pub fn try_complex(cells_arg : Vec<Cell>) -> Result<Cell, SRLError> {
 	if cells_arg.len() < 2 {
-		panic!("complex cell neeeds more than 1 argument");
+		return Err(SRLError::invalid_cell("complex", CellErrorKind::ShortComplex));
+	}
+	Ok(Cell::Complex { cells : cells_arg })
+}
+
+pub fn complex(cells_arg : Vec<Cell>) -> Cell {
+	match try_complex(cells_arg) {
+		Ok(x) => x,
+		Err(srl_error) => panic!("{}", srl_error)
 	}
-	Cell::Complex { cells : cells_arg }
 }
 
 pub fn scope(id : u32, body : Cell) -> Cell {
@@ -0,0 +1,468 @@
+use cell::{Cell, CellType};
+use error::{SRLError, LawError, LawErrorKind, NavigationErrorKind};
+use gen::*;
+use law::{Law, LawArgument};
+
+// a small proof checker, that only relies on the Cell type itself, the law names (law.rs) and the error types
+// (no CellPath, CellCursor, Wrapper or Database), so it can be audited on its own.
+// it must not panic, whatever the certificate contains.
+// the checks are the same as in db::reason, in the same order, so both fail with the same LawErrorKind.
+//
+// theorem i is axioms[i] for i < axioms.len(), otherwise the result of steps[i - axioms.len()];
+// a step may only use the theorems in front of its own result, that have not been forgotten (deleted rules) yet.
+
+// (theorem index, indices)
+pub type Position = (usize, Vec<usize>);
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct Step {
+	law : Law,
+	inputs : Vec<Position>,
+	argument : Option<LawArgument>,
+	forgotten : Vec<usize> // theorems, that are not available from this step on
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct Certificate {
+	axioms : Vec<Cell>,
+	steps : Vec<Step>
+}
+
+struct Polarity {
+	positive : bool,
+	nallq : bool,
+	nexq : bool
+}
+
+impl Step {
+	pub fn create(law : Law, inputs : Vec<Position>, argument : Option<LawArgument>) -> Step {
+		Step { law : law, inputs : inputs, argument : argument, forgotten : Vec::new() }
+	}
+
+	pub fn with_forgotten(mut self, forgotten : Vec<usize>) -> Step {
+		self.forgotten = forgotten;
+		self
+	}
+
+	pub fn get_law(&self) -> Law { self.law }
+	pub fn get_inputs(&self) -> &[Position] { &self.inputs }
+	pub fn get_argument(&self) -> Option<&LawArgument> { self.argument.as_ref() }
+	pub fn get_forgotten(&self) -> &[usize] { &self.forgotten }
+}
+
+impl Certificate {
+	pub fn create(axioms : Vec<Cell>, steps : Vec<Step>) -> Certificate {
+		Certificate { axioms : axioms, steps : steps }
+	}
+
+	pub fn get_axioms(&self) -> &[Cell] { &self.axioms }
+	pub fn get_steps(&self) -> &[Step] { &self.steps }
+
+	// the (normalized) results of all steps, or the index of the first failing step and why it failed
+	pub fn check(&self) -> Result<Vec<Cell>, (usize, SRLError)> {
+		let mut theorems : Vec<Option<Cell>> = self.axioms.iter().cloned().map(Some).collect();
+		let mut results = Vec::new();
+		for (i, step) in self.steps.iter().enumerate() {
+			for &index in &step.forgotten {
+				match theorems.get_mut(index) {
+					Some(x) => *x = None,
+					None => return Err((i, SRLError::navigation("kernel::check", NavigationErrorKind::RuleOutOfRange)))
+				}
+			}
+			match check_step(&theorems, step) {
+				Ok(x) => {
+					theorems.push(Some(x.clone()));
+					results.push(x);
+				},
+				Err(srl_error) => return Err((i, srl_error))
+			}
+		}
+		Ok(results)
+	}
+}
+
+// theorems[i] is None, if theorem i has been forgotten
+pub fn check_step(theorems : &[Option<Cell>], step : &Step) -> Result<Cell, SRLError> {
+	let rule = match (step.law, step.inputs.as_slice(), &step.argument) {
+		(Law::EqualsLaw, &[ref src, ref evidence], &None) => equals_law(theorems, src, evidence)?,
+		(Law::EqualsLawImpl, &[ref src, ref evidence], &None) => equals_law_impl(theorems, src, evidence)?,
+		(Law::InequalConstants, &[ref pos], &None) => inequal_constants(theorems, pos)?,
+		(Law::AddEqt, &[ref pos], &None) => add_eqt(theorems, pos)?,
+		(Law::RmEqt, &[ref pos], &None) => rm_eqt(theorems, pos)?,
+		(Law::ScopeInsertion, &[ref pos], &Some(LawArgument::Cell(ref cell))) => scope_insertion(theorems, pos, cell)?,
+		(Law::ScopeCreation, &[ref pos], &Some(LawArgument::Paths(ref paths))) => scope_creation(theorems, pos, paths)?,
+		(Law::ImplicationsDerivation, &[ref case_pos, ref negation_pos], &None) => implications_derivation(theorems, case_pos, negation_pos)?,
+		(Law::ScopeExchange, &[ref pos], &None) => scope_exchange(theorems, pos)?,
+		(Law::CaseCreation, &[ref pos], &Some(LawArgument::Cell(ref cell))) => case_creation(theorems, pos, cell)?,
+		(Law::Declaration, &[ref pos], &Some(LawArgument::Name(ref name))) => declaration(theorems, pos, name)?,
+		_ => return Err(fail(step.law, LawErrorKind::WrongArguments, "wrong arguments"))
+	};
+	rule.get_normalized()
+}
+
+// helpers
+
+fn fail(law : Law, kind : LawErrorKind, message : &str) -> SRLError {
+	LawError::create(law, kind, message).into()
+}
+
+fn rule_at<'a>(theorems : &'a [Option<Cell>], pos : &Position) -> Result<&'a Cell, SRLError> {
+	match theorems.get(pos.0) {
+		Some(&Some(ref x)) => Ok(x),
+		_ => Err(SRLError::navigation("kernel::rule_at", NavigationErrorKind::RuleOutOfRange))
+	}
+}
+
+fn cell_at<'a>(root : &'a Cell, indices : &[usize]) -> Result<&'a Cell, SRLError> {
+	match root.path_ref(indices) {
+		Some(x) => Ok(x),
+		None => Err(SRLError::navigation("kernel::cell_at", NavigationErrorKind::InvalidIndex))
+	}
+}
+
+fn replaced(root : &Cell, indices : &[usize], cell : Cell) -> Result<Cell, SRLError> {
+	let mut root = root.clone();
+	root.replace_subcell(indices, cell)?;
+	Ok(root)
+}
+
+fn is_complete_bool(cell : &Cell) -> bool {
+	if let Cell::Scope {..} = *cell { return true; }
+	cell.get_equals_cell_arguments().is_ok() || *cell == true_cell() || *cell == false_cell()
+}
+
+fn is_bool(root : &Cell, indices : &[usize]) -> Result<bool, SRLError> {
+	let parent = match indices.split_last() {
+		Some((_, x)) => x,
+		None => return Ok(true)
+	};
+	if is_complete_bool(cell_at(root, indices)?) {
+		return Ok(true);
+	}
+	match cell_at(root, parent)? {
+		&Cell::Scope {..} | &Cell::Case {..} => Ok(true),
+		_ => Ok(false)
+	}
+}
+
+// the wrapper of the cell at indices: every cell above it is a scope, the conclusion of a case or (= 'false' <x>)
+fn polarity(root : &Cell, indices : &[usize]) -> Option<Polarity> {
+	let mut polarity = Polarity { positive : true, nallq : true, nexq : true };
+	let mut cell = root;
+	for &index in indices {
+		match cell {
+			&Cell::Scope {..} if index == 0 => {
+				if polarity.positive {
+					polarity.nallq = false;
+				} else {
+					polarity.nexq = false;
+				}
+			},
+			&Cell::Case {..} if index == 1 => continue, // like Wrapper, case cells are not entered
+			&Cell::Complex { ref cells } if index == 2 && cells.len() == 3 && cells[0] == simple_by_str("=") && cells[1] == false_cell() => {
+				polarity.positive = !polarity.positive;
+			},
+			_ => return None
+		}
+		cell = cell.get_subcell_ref(index)?;
+	}
+	Some(polarity)
+}
+
+// whether the wrapper at (wrapper_root, wrapper_indices) is around the cell at (root, indices),
+// i.e. all cells above the wrapper are equal up to the path
+// -- like Wrapper::is_around, a cell above the wrapper is considered to be inside of it
+fn is_around(wrapper_root : &Cell, wrapper_indices : &[usize], root : &Cell, indices : &[usize]) -> Result<bool, SRLError> {
+	let mut cell1 = wrapper_root;
+	let mut cell2 = root;
+	for (i, &index) in wrapper_indices.iter().enumerate() {
+		if i >= indices.len() {
+			return Ok(true);
+		}
+		if index != indices[i] {
+			return Ok(false);
+		}
+		if cell1.try_with_subcell(false_cell(), index)? != cell2.try_with_subcell(false_cell(), index)? {
+			return Ok(false);
+		}
+		cell1 = cell_at(cell1, &[index])?;
+		cell2 = cell_at(cell2, &[index])?;
+	}
+	Ok(true)
+}
+
+// the replacement for src, if it matches one side of the equals cell
+fn other_side(law : Law, evidence : &Cell, src : &Cell) -> Result<Cell, SRLError> {
+	let (a, b) = evidence.get_equals_cell_arguments()?;
+	if a.matches(src) {
+		Ok(b)
+	} else if b.matches(src) {
+		Ok(a)
+	} else {
+		Err(LawError::create(law, LawErrorKind::NoMatch, "replace cell does not occur in evidence").with_cells(evidence.clone(), src.clone()).into())
+	}
+}
+
+// laws, see db::reason for examples
+
+fn equals_law(theorems : &[Option<Cell>], src : &Position, evidence : &Position) -> Result<Cell, SRLError> {
+	let src_root = rule_at(theorems, src)?;
+	let evidence_root = rule_at(theorems, evidence)?;
+	let src_cell = cell_at(src_root, &src.1)?;
+	let evidence_cell = cell_at(evidence_root, &evidence.1)?;
+
+	let polarity = match polarity(evidence_root, &evidence.1) {
+		Some(x) => x,
+		None => return Err(fail(Law::EqualsLaw, LawErrorKind::NoWrapper, "evidence is not in wrapper"))
+	};
+	if !polarity.nexq {
+		return Err(fail(Law::EqualsLaw, LawErrorKind::WrapperNotNexq, "wrapper is no nexq-wrapper"));
+	}
+	evidence_cell.get_equals_cell_arguments()?;
+	if !is_around(evidence_root, &evidence.1, src_root, &src.1)? {
+		return Err(fail(Law::EqualsLaw, LawErrorKind::NotInWrapper, "src and evidence are not in the same wrapper"));
+	}
+	let new = other_side(Law::EqualsLaw, evidence_cell, src_cell)?;
+	replaced(src_root, &src.1, new)
+}
+
+fn equals_law_impl(theorems : &[Option<Cell>], src : &Position, evidence : &Position) -> Result<Cell, SRLError> {
+	let src_root = rule_at(theorems, src)?;
+	let evidence_root = rule_at(theorems, evidence)?;
+	let src_cell = cell_at(src_root, &src.1)?;
+	let evidence_cell = cell_at(evidence_root, &evidence.1)?;
+
+	let case_indices = match evidence.1.split_last() {
+		Some((&0, x)) => x,
+		_ => return Err(fail(Law::EqualsLawImpl, LawErrorKind::NotCaseCondition, "evidence can't be condition of case-cell"))
+	};
+	if let &Cell::Case {..} = cell_at(evidence_root, case_indices)? {} else {
+		return Err(fail(Law::EqualsLawImpl, LawErrorKind::NotCaseCondition, "evidence can't be condition of case-cell (2)"));
+	}
+	if src.0 != evidence.0 {
+		return Err(fail(Law::EqualsLawImpl, LawErrorKind::DifferentRules, "src and evidence are not in the same rule"));
+	}
+	let mut conclusion_indices = case_indices.to_vec();
+	conclusion_indices.push(1);
+	if polarity(evidence_root, &conclusion_indices).is_none() {
+		return Err(fail(Law::EqualsLawImpl, LawErrorKind::NoWrapper, "no wrapper!"));
+	}
+	if !is_around(evidence_root, &conclusion_indices, src_root, &src.1)? {
+		return Err(fail(Law::EqualsLawImpl, LawErrorKind::NotInWrapper, "evi-wrapper is not around src"));
+	}
+	let new = other_side(Law::EqualsLawImpl, evidence_cell, src_cell)?;
+	replaced(src_root, &src.1, new)
+}
+
+fn inequal_constants(theorems : &[Option<Cell>], pos : &Position) -> Result<Cell, SRLError> {
+	let root = rule_at(theorems, pos)?;
+	let (x, y) = cell_at(root, &pos.1)?.get_equals_cell_arguments()?;
+	if !x.is_constant() {
+		return Err(fail(Law::InequalConstants, LawErrorKind::NotConstant, "first arg not constant"));
+	}
+	if !y.is_constant() {
+		return Err(fail(Law::InequalConstants, LawErrorKind::NotConstant, "second arg is not constant"));
+	}
+	if x == y {
+		return Err(fail(Law::InequalConstants, LawErrorKind::EqualConstants, "both args equal"));
+	}
+	replaced(root, &pos.1, false_cell())
+}
+
+fn add_eqt(theorems : &[Option<Cell>], pos : &Position) -> Result<Cell, SRLError> {
+	let root = rule_at(theorems, pos)?;
+	let cell = cell_at(root, &pos.1)?;
+	if !is_bool(root, &pos.1)? {
+		return Err(fail(Law::AddEqt, LawErrorKind::NotBool, "cell is not bool"));
+	}
+	replaced(root, &pos.1, equals_cell(true_cell(), cell.clone()))
+}
+
+fn rm_eqt(theorems : &[Option<Cell>], pos : &Position) -> Result<Cell, SRLError> {
+	let root = rule_at(theorems, pos)?;
+	let cell = cell_at(root, &pos.1)?;
+	let parent_indices = match pos.1.split_last() {
+		Some((_, x)) => x,
+		None => return Err(fail(Law::RmEqt, LawErrorKind::NoParent, "cell has no parents"))
+	};
+	let (a, b) = match cell_at(root, parent_indices)?.get_equals_cell_arguments() {
+		Ok(x) => x,
+		Err(_) => return Err(fail(Law::RmEqt, LawErrorKind::NotInEquals, "not contained in equals cell"))
+	};
+	if a != true_cell() {
+		return Err(LawError::create(Law::RmEqt, LawErrorKind::CellMismatch, "first cell in equals is not 'true'").with_cells(true_cell(), a).into());
+	}
+	if b != *cell {
+		return Err(LawError::create(Law::RmEqt, LawErrorKind::CellMismatch, "second cell in equals is not cell").with_cells(cell.clone(), b).into());
+	}
+	let rule = replaced(root, parent_indices, cell.clone())?;
+	if !is_bool(&rule, parent_indices)? {
+		return Err(fail(Law::RmEqt, LawErrorKind::NotBool, "result is no bool-cell"));
+	}
+	Ok(rule)
+}
+
+fn scope_insertion(theorems : &[Option<Cell>], pos : &Position, cell : &Cell) -> Result<Cell, SRLError> {
+	let root = rule_at(theorems, pos)?;
+	let (id, body) = match cell_at(root, &pos.1)? {
+		&Cell::Scope { id, ref body } => (id, body),
+		_ => return Err(fail(Law::ScopeInsertion, LawErrorKind::WrongCellType { expected : CellType::Scope }, "pos does not represent scope"))
+	};
+	if !is_complete_bool(body) {
+		return Err(fail(Law::ScopeInsertion, LawErrorKind::NotCompleteBool, "body is no complete bool cell"));
+	}
+	match polarity(root, &pos.1) {
+		None => return Err(fail(Law::ScopeInsertion, LawErrorKind::NoWrapper, "no wrapper")),
+		Some(ref x) if !x.positive => return Err(fail(Law::ScopeInsertion, LawErrorKind::WrapperNotPositive, "wrapper is not positive")),
+		_ => {}
+	}
+
+	// every inserted copy gets its own fresh scope ids
+	let mut next_id = root.get_next_id() as u32;
+	let id_amount = cell.get_normalized()?.get_next_id() as u32;
+	let var_paths : Vec<Vec<usize>> = body.pre_order()
+		.filter(|&(x, _)| *x == var(id))
+		.map(|(_, indices)| indices)
+		.collect();
+	let mut new_body = (**body).clone();
+	for indices in var_paths {
+		new_body.replace_subcell(&indices, cell.get_normalized_from(next_id)?)?;
+		next_id += id_amount;
+	}
+	replaced(root, &pos.1, new_body)
+}
+
+fn scope_creation(theorems : &[Option<Cell>], pos : &Position, paths : &[Vec<usize>]) -> Result<Cell, SRLError> {
+	let root = rule_at(theorems, pos)?;
+	let cell = cell_at(root, &pos.1)?;
+	if !is_complete_bool(cell) {
+		return Err(fail(Law::ScopeCreation, LawErrorKind::NotCompleteBool, "pos does not contain a complete bool-cell"));
+	}
+	match polarity(root, &pos.1) {
+		None => return Err(fail(Law::ScopeCreation, LawErrorKind::NoWrapper, "no wrapper")),
+		Some(ref x) if x.positive => return Err(fail(Law::ScopeCreation, LawErrorKind::WrapperPositive, "wrapper is positive")),
+		_ => {}
+	}
+	for pair in paths.windows(2) {
+		let cell1 = cell_at(cell, &pair[0])?;
+		let cell2 = cell_at(cell, &pair[1])?;
+		if !cell1.matches(cell2) {
+			return Err(LawError::create(Law::ScopeCreation, LawErrorKind::CellMismatch, "paths do not represent the same cells").with_cells(cell1.clone(), cell2.clone()).into());
+		}
+	}
+
+	let new_id = root.get_next_id() as u32;
+	let mut body = cell.clone();
+	for indices in paths {
+		body.replace_subcell(indices, var(new_id))?;
+	}
+	replaced(root, &pos.1, scope(new_id, body))
+}
+
+fn implications_derivation(theorems : &[Option<Cell>], case_pos : &Position, negation_pos : &Position) -> Result<Cell, SRLError> {
+	let case_root = rule_at(theorems, case_pos)?;
+	let negation_root = rule_at(theorems, negation_pos)?;
+	let case_cell = cell_at(case_root, &case_pos.1)?;
+	let negation_cell = cell_at(negation_root, &negation_pos.1)?;
+	let (condition, conclusion) = match case_cell {
+		&Cell::Case { ref condition, ref conclusion } => (condition, conclusion),
+		_ => return Err(fail(Law::ImplicationsDerivation, LawErrorKind::WrongCellType { expected : CellType::Case }, "case_pos does not represent case-cell"))
+	};
+	let (negation_condition, negation_conclusion) = match negation_cell {
+		&Cell::Case { ref condition, ref conclusion } => (condition, conclusion),
+		_ => return Err(fail(Law::ImplicationsDerivation, LawErrorKind::WrongCellType { expected : CellType::Case }, "negation_pos does not represent case-cell"))
+	};
+	if conclusion != negation_conclusion {
+		return Err(LawError::create(Law::ImplicationsDerivation, LawErrorKind::CellMismatch, "conclusions differ").with_cells((**conclusion).clone(), (**negation_conclusion).clone()).into());
+	}
+	let expected_condition = equals_cell(false_cell(), (**condition).clone());
+	if expected_condition != **negation_condition {
+		return Err(LawError::create(Law::ImplicationsDerivation, LawErrorKind::CellMismatch, "conditions are not correct").with_cells(expected_condition, (**negation_condition).clone()).into());
+	}
+
+	let polarity = match polarity(case_root, &case_pos.1) {
+		Some(x) => x,
+		None => return Err(fail(Law::ImplicationsDerivation, LawErrorKind::NoWrapper, "no wrapper (1)"))
+	};
+	if self::polarity(negation_root, &negation_pos.1).is_none() {
+		return Err(fail(Law::ImplicationsDerivation, LawErrorKind::NoWrapper, "no wrapper (2)"));
+	}
+	// both wrappers have to be the same cell
+	if case_pos.1 != negation_pos.1 || replaced(case_root, &case_pos.1, false_cell())? != replaced(negation_root, &negation_pos.1, false_cell())? {
+		return Err(fail(Law::ImplicationsDerivation, LawErrorKind::DifferentWrappers, "different wrappers"));
+	}
+	if !polarity.nexq {
+		return Err(fail(Law::ImplicationsDerivation, LawErrorKind::WrapperNotNexq, "wrapper contains existance quantor"));
+	}
+	if !polarity.positive {
+		return Err(fail(Law::ImplicationsDerivation, LawErrorKind::WrapperNotPositive, "wrapper is negative"));
+	}
+	Ok((**conclusion).clone())
+}
+
+fn scope_exchange(theorems : &[Option<Cell>], pos : &Position) -> Result<Cell, SRLError> {
+	let root = rule_at(theorems, pos)?;
+	let outer = cell_at(root, &pos.1)?;
+	let inner = match outer.get_subcell_ref(0) {
+		Some(x) => x,
+		None => return Err(SRLError::navigation("kernel::scope_exchange", NavigationErrorKind::InvalidIndex))
+	};
+	let outer_id = match outer {
+		&Cell::Scope { id, .. } => id,
+		_ => return Err(fail(Law::ScopeExchange, LawErrorKind::WrongCellType { expected : CellType::Scope }, "outer cell is no scope"))
+	};
+	let (inner_id, body) = match inner {
+		&Cell::Scope { id, ref body } => (id, body),
+		_ => return Err(fail(Law::ScopeExchange, LawErrorKind::WrongCellType { expected : CellType::Scope }, "inner cell is no scope"))
+	};
+	replaced(root, &pos.1, scope(inner_id, scope(outer_id, (**body).clone())))
+}
+
+fn case_creation(theorems : &[Option<Cell>], pos : &Position, condition : &Cell) -> Result<Cell, SRLError> {
+	let root = rule_at(theorems, pos)?;
+	let cell = cell_at(root, &pos.1)?;
+	match polarity(root, &pos.1) {
+		None => return Err(fail(Law::CaseCreation, LawErrorKind::NoWrapper, "no wrapper")),
+		Some(ref x) if !x.positive => return Err(fail(Law::CaseCreation, LawErrorKind::WrapperNotPositive, "wrapper is not positive")),
+		_ => {}
+	}
+	replaced(root, &pos.1, case(condition.clone(), cell.clone()))
+}
+
+fn declaration(theorems : &[Option<Cell>], pos : &Position, name : &str) -> Result<Cell, SRLError> {
+	let occurs = theorems.iter().flat_map(|x| x).any(|rule| rule.pre_order().any(|(x, _)| match x {
+		&Cell::Simple { ref string } => string.get_string() == name,
+		_ => false
+	}));
+	if occurs {
+		return Err(fail(Law::Declaration, LawErrorKind::NameOccurs, "name does already occur"));
+	}
+	let root = rule_at(theorems, pos)?;
+	let cell = cell_at(root, &pos.1)?;
+	let polarity = match polarity(root, &pos.1) {
+		Some(x) => x,
+		None => return Err(fail(Law::Declaration, LawErrorKind::NoWrapper, "no wrapper"))
+	};
+	if !polarity.positive {
+		return Err(fail(Law::Declaration, LawErrorKind::WrapperNotPositive, "wrapper is negative"));
+	}
+	if !polarity.nallq {
+		return Err(fail(Law::Declaration, LawErrorKind::WrapperNotNallq, "wrapper contains all quantor"));
+	}
+
+	// (= 'false' {0 (= 'false' b)})
+	let (x, y) = cell.get_equals_cell_arguments()?;
+	if x != false_cell() {
+		return Err(LawError::create(Law::Declaration, LawErrorKind::CellMismatch, "first arg of equals cell is not 'false'").with_cells(false_cell(), x).into());
+	}
+	let (id, body) = match y {
+		Cell::Scope { id, body } => (id, body),
+		_ => return Err(fail(Law::Declaration, LawErrorKind::WrongCellType { expected : CellType::Scope }, "second arg is no scope"))
+	};
+	let (a, b) = body.get_equals_cell_arguments()?;
+	if a != false_cell() {
+		return Err(LawError::create(Law::Declaration, LawErrorKind::CellMismatch, "scope does not contain (= 'false' *)").with_cells(false_cell(), a).into());
+	}
+	let new = b.replace_all(var(id), try_simple(name.to_string())?);
+	replaced(root, &pos.1, new)
+}
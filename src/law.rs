@@ -0,0 +1,71 @@
+use std::fmt;
+use cell::Cell;
+
+// the names of the laws and their extra arguments, shared by db::reason and the kernel
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Law {
+	EqualsLaw,
+	EqualsLawImpl,
+	InequalConstants,
+	AddEqt,
+	RmEqt,
+	ScopeInsertion,
+	ScopeCreation,
+	ImplicationsDerivation,
+	ScopeExchange,
+	CaseCreation,
+	Declaration
+}
+
+impl Law {
+	pub fn by_name(name : &str) -> Option<Law> {
+		let laws = [Law::EqualsLaw, Law::EqualsLawImpl, Law::InequalConstants, Law::AddEqt, Law::RmEqt, Law::ScopeInsertion,
+			Law::ScopeCreation, Law::ImplicationsDerivation, Law::ScopeExchange, Law::CaseCreation, Law::Declaration];
+		laws.iter().find(|x| x.get_name() == name).cloned()
+	}
+
+	pub fn get_name(&self) -> &'static str {
+		match self {
+			&Law::EqualsLaw => "equals_law",
+			&Law::EqualsLawImpl => "equals_law_impl",
+			&Law::InequalConstants => "inequal_constants",
+			&Law::AddEqt => "add_eqt",
+			&Law::RmEqt => "rm_eqt",
+			&Law::ScopeInsertion => "scope_insertion",
+			&Law::ScopeCreation => "scope_creation",
+			&Law::ImplicationsDerivation => "implications_derivation",
+			&Law::ScopeExchange => "scope_exchange",
+			&Law::CaseCreation => "case_creation",
+			&Law::Declaration => "declaration"
+		}
+	}
+}
+
+// the argument of a law besides its input cells
+#[derive(Clone, PartialEq, Debug)]
+pub enum LawArgument {
+	Cell(Cell),
+	Paths(Vec<Vec<usize>>),
+	Name(String)
+}
+
+fn paths_to_string(paths : &Vec<Vec<usize>>) -> String {
+	let strings : Vec<String> = paths.iter()
+		.map(|path| {
+			let indices : Vec<String> = path.iter().map(|x| x.to_string()).collect();
+			format!("/{}", indices.join("."))
+		})
+		.collect();
+	strings.join(" ")
+}
+
+impl fmt::Display for LawArgument {
+	fn fmt(&self, f : &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			&LawArgument::Cell(ref cell) => write!(f, "{}", cell),
+			&LawArgument::Paths(ref paths) => f.write_str(&paths_to_string(paths)),
+			&LawArgument::Name(ref name) => f.write_str(name)
+		}
+	}
+}
@@ -1,8 +1,16 @@
 pub mod db;
 pub mod cell;
 pub mod navi;
+pub mod cursor;
 pub mod error;
 pub mod gen;
+pub mod visit;
+pub mod validate;
+pub mod typing;
+pub mod pattern;
+pub mod query;
+pub mod kernel;
+pub mod law;
 mod normalize;
 mod parse;
 mod misc;
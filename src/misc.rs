@@ -1,5 +1,5 @@
 use cell::Cell;
-use error::SRLError;
+use error::{SRLError, CellErrorKind};
 use gen::*;
 
 pub fn contains_only(string : String, list : String) -> bool {
@@ -24,18 +24,33 @@ pub fn index_in_len(index : usize, len : usize) -> bool {
 	index < len
 }
 
+// FNV-1a, stable across builds (unlike the std hashers)
+pub fn checksum(bytes : &[u8]) -> u64 {
+	bytes.iter().fold(0xcbf29ce484222325, |hash, &byte| (hash ^ byte as u64).wrapping_mul(0x100000001b3))
+}
+
+// whether cell violates the invariant of gen::complex somewhere
+pub fn has_short_complex(cell : &Cell) -> bool {
+	cell.pre_order().any(|(x, _)| {
+		match x {
+			&Cell::Complex { cells : ref cells_out } => cells_out.len() < 2,
+			_ => false
+		}
+	})
+}
+
 impl Cell {
 	pub fn get_equals_cell_arguments(&self) -> Result<(Cell, Cell), SRLError> {
 		if let &Cell::Complex { cells : ref cells_out } = self {
 			if cells_out.len() != 3 {
-				return Err(SRLError("get_equals_cell_arguments".to_string(), "complex cell does not have 3 arguments".to_string()));
+				return Err(SRLError::invalid_cell("get_equals_cell_arguments", CellErrorKind::EqualsArgumentCount));
 			}
 			if cells_out[0] != simple_by_str("=") {
-				return Err(SRLError("get_equals_cell_arguments".to_string(), "first cell is not =".to_string()));
+				return Err(SRLError::invalid_cell("get_equals_cell_arguments", CellErrorKind::NoEqualsSymbol));
 			}
 			return Ok((cells_out[1].clone(), cells_out[2].clone()));
 		} else {
-			return Err(SRLError("get_equals_cell_arguments".to_string(), "cell is not complex".to_string()));
+			return Err(SRLError::invalid_cell("get_equals_cell_arguments", CellErrorKind::NotComplex));
 		}
 	}
 }
@@ -1,11 +1,25 @@
+use std::fmt;
+use std::str::FromStr;
 use cell::{Cell, CellType};
-use error::SRLError;
+use error::{SRLError, NavigationErrorKind};
 use misc::*;
 use gen::*;
 
+// stable name of a rule; unlike its index it does not change when other rules are deleted
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct RuleHandle(u64);
+
+impl RuleHandle {
+	pub fn create(id : u64) -> RuleHandle { RuleHandle(id) }
+	pub fn get_id(&self) -> u64 { self.0 }
+}
+
+// rule_id is the position of the rule in the database at the time of creation,
+// if a handle is set, it is authoritative and rule_id is looked up again (see Database::locate)
 #[derive(Clone, PartialEq)]
 pub struct CellID {
 	rule_id : usize,
+	handle : Option<RuleHandle>,
 	indices : Vec<usize>
 }
 
@@ -17,56 +31,67 @@ pub struct CellPath {
 
 impl CellID {
 	pub fn create(rule_index : usize, indices : Vec<usize>) -> CellID {
-		CellID { rule_id : rule_index, indices : indices }
+		CellID { rule_id : rule_index, handle : None, indices : indices }
+	}
+
+	pub fn with_handle(mut self, handle : RuleHandle) -> CellID {
+		self.handle = Some(handle);
+		self
 	}
 
 	pub fn get_path(&self, rules : &Vec<Cell>) -> Result<CellPath, SRLError> {
 		if index_in_len(self.rule_id, rules.len()) {
 			CellPath::create(rules[self.rule_id].clone(), self.indices.clone())
 		} else {
-			Err(SRLError("CellID::to_path".to_string(), "index of rule_id out of range".to_string()))
+			Err(SRLError::navigation("CellID::to_path", NavigationErrorKind::RuleOutOfRange))
 		}
 	}
 
 	pub fn get_rule_id(&self) -> usize { self.rule_id.clone() }
+	pub fn get_handle(&self) -> Option<RuleHandle> { self.handle }
 	pub fn get_indices(&self) -> Vec<usize> { self.indices.clone() }
 
 	pub fn get_parent(&self) -> Result<CellID, SRLError> {
 		let mut vec = self.indices.clone();
 		return match vec.pop() {
-			Some(_) => Ok(CellID::create(self.rule_id, vec)),
-			None => Err(SRLError("CellID::get_parent".to_string(), "no parent".to_string()))
+			Some(_) => Ok(CellID { rule_id : self.rule_id, handle : self.handle, indices : vec }),
+			None => Err(SRLError::navigation("CellID::get_parent", NavigationErrorKind::NoParent))
 		}
 	}
 
 	pub fn get_child(&self, index : usize) -> Result<CellID, SRLError> {
 		let mut vec = self.indices.clone();
 		vec.push(index);
-		Ok(CellID::create(self.rule_id, vec))
+		Ok(CellID { rule_id : self.rule_id, handle : self.handle, indices : vec })
 	}
 
 	pub fn get_left_sibling(&self) -> Result<CellID, SRLError> {
 		let mut vec = self.indices.clone();
 		let index = match vec.pop() {
 			Some(x) => x,
-			None => return Err(SRLError("CellID::get_left_sibling".to_string(), "no parent".to_string()))
+			None => return Err(SRLError::navigation("CellID::get_left_sibling", NavigationErrorKind::NoParent))
 		};
 		if index == 0 {
-			return Err(SRLError("CellID::get_left_sibling".to_string(), "no left sibling".to_string()));
+			return Err(SRLError::navigation("CellID::get_left_sibling", NavigationErrorKind::NoLeftSibling));
 		}
 
 		vec.push(index - 1);
-		return Ok(CellID::create(self.rule_id, vec));
+		return Ok(CellID { rule_id : self.rule_id, handle : self.handle, indices : vec });
 	}
 
-	pub fn get_right_sibling(&self) -> Result<CellID, SRLError> {
+	// unlike the other moves, this needs the rules to know whether the sibling exists
+	pub fn get_right_sibling(&self, rules : &Vec<Cell>) -> Result<CellID, SRLError> {
 		let mut vec = self.indices.clone();
 		let index = match vec.pop() {
 			Some(x) => x,
-			None => return Err(SRLError("CellID::get_right_sibling".to_string(), "no parent".to_string()))
+			None => return Err(SRLError::navigation("CellID::get_right_sibling", NavigationErrorKind::NoParent))
 		};
 		vec.push(index + 1);
-		return Ok(CellID::create(self.rule_id, vec));
+		let sibling = CellID { rule_id : self.rule_id, handle : self.handle, indices : vec };
+		if !self.is_valid(rules) || !sibling.is_valid(rules) {
+			return Err(SRLError::navigation("CellID::get_right_sibling", NavigationErrorKind::NoRightSibling));
+		}
+		return Ok(sibling);
 	}
 
 	pub fn is_valid(&self, rules : &Vec<Cell>) -> bool {
@@ -80,23 +105,17 @@ impl CellID {
 impl CellPath {
 	pub fn create(root_cell : Cell, indices : Vec<usize>) -> Result<CellPath, SRLError> {
 		// error test
-		let mut cell = root_cell.clone();
-		for index in indices.clone() {
-			if index_in_len(index, cell.count_subcells()) {
-				cell = cell.get_subcell(index);
-			} else {
-				return Err(SRLError("CellPath::create".to_string(), "index is unacceptable!".to_string()));
-			}
+		if root_cell.path_ref(&indices).is_none() {
+			return Err(SRLError::navigation("CellPath::create", NavigationErrorKind::InvalidIndex));
 		}
 		Ok(CellPath { root_cell : root_cell, indices : indices })
 	}
 
 	pub fn get_cell(&self) -> Cell {
-		let mut cell = self.root_cell.clone();
-		for index in self.indices.clone() {
-			cell = cell.get_subcell(index);
+		match self.root_cell.path_ref(&self.indices) {
+			Some(x) => x.clone(),
+			None => panic!("CellPath::get_cell: invalid indices - should not happen")
 		}
-		cell
 	}
 
 	pub fn is_complete_bool(&self) -> bool {
@@ -129,7 +148,7 @@ impl CellPath {
 		let mut vec = self.indices.clone();
 		return match vec.pop() {
 			Some(_) => CellPath::create(self.root_cell.clone(), vec),
-			None => Err(SRLError("CellPath::get_parent".to_string(), "no parent".to_string()))
+			None => Err(SRLError::navigation_at("CellPath::get_parent", NavigationErrorKind::NoParent, self))
 		}
 	}
 
@@ -143,10 +162,10 @@ impl CellPath {
 		let mut vec = self.indices.clone();
 		let index = match vec.pop() {
 			Some(x) => x,
-			None => return Err(SRLError("CellPath::get_right_sibling".to_string(), "no parent".to_string()))
+			None => return Err(SRLError::navigation_at("CellPath::get_right_sibling", NavigationErrorKind::NoParent, self))
 		};
 		if index == 0 {
-			return Err(SRLError("CellPath::get_left_sibling".to_string(), "no left sibling".to_string()))
+			return Err(SRLError::navigation_at("CellPath::get_left_sibling", NavigationErrorKind::NoLeftSibling, self))
 		}
 
 		let parent = self.get_parent()?;
@@ -157,29 +176,23 @@ impl CellPath {
 		let mut vec = self.indices.clone();
 		let index = match vec.pop() {
 			Some(x) => x,
-			None => return Err(SRLError("CellPath::get_right_sibling".to_string(), "no parent".to_string()))
+			None => return Err(SRLError::navigation_at("CellPath::get_right_sibling", NavigationErrorKind::NoParent, self))
 		};
 		let parent = self.get_parent()?;
 		return parent.get_child(index + 1);
 	}
 
-	pub fn replace_by(&self, mut cell : Cell) -> Cell {
-		let mut indices = self.indices.clone();
-		let mut last_index;
+	pub fn try_replace_by(&self, cell : Cell) -> Result<Cell, SRLError> {
+		let mut root = self.root_cell.clone();
+		root.replace_subcell(&self.indices, cell)?;
+		Ok(root)
+	}
 
-		while indices.len() > 0 {
-			last_index = match indices.pop() {
-				Some(x) => x,
-				None => panic!("CellPath.replace_by: failure 1 - should not happen")
-			};
-			let cell_path = match CellPath::create(self.root_cell.clone(), indices.clone()) {
-				Ok(x) => x,
-				Err(srl_error) => panic!("CellPath::replace_by is invalid: {:?}", srl_error)
-			};
-			let tmp = cell_path.get_cell();
-			cell = tmp.with_subcell(cell, last_index);
+	pub fn replace_by(&self, cell : Cell) -> Cell {
+		match self.try_replace_by(cell) {
+			Ok(x) => x,
+			Err(srl_error) => panic!("CellPath::replace_by: {}", srl_error)
 		}
-		cell
 	}
 
 	pub fn get_type(&self) -> CellType {
@@ -187,7 +200,109 @@ impl CellPath {
 	}
 
 	pub fn get_root_cell(&self) -> Cell { self.root_cell.clone() }
+	pub fn get_root_ref(&self) -> &Cell { &self.root_cell }
 	pub fn get_indices(&self) -> Vec<usize> { self.indices.clone() }
+
+	// the root cell (as rule, without outer parens) with the focused subcell wrapped in <>
+	pub fn to_highlighted_string(&self) -> String {
+		if self.indices.is_empty() {
+			return format!("<{}>", self.root_cell.to_unwrapped_string());
+		}
+		if let Cell::Complex { ref cells } = self.root_cell {
+			return highlighted_subcells(cells, &self.indices);
+		}
+		highlighted_r(&self.root_cell, &self.indices)
+	}
+}
+
+fn highlighted_subcells(cells : &Vec<Cell>, indices : &[usize]) -> String {
+	let strings : Vec<String> = cells.iter()
+		.enumerate()
+		.map(|(i, x)| if i == indices[0] { highlighted_r(x, &indices[1..]) } else { x.to_string() })
+		.collect();
+	strings.join(" ")
+}
+
+fn highlighted_r(cell : &Cell, indices : &[usize]) -> String {
+	if indices.is_empty() {
+		return format!("<{}>", cell.to_string());
+	}
+	match cell {
+		&Cell::Complex { ref cells } => format!("({})", highlighted_subcells(cells, indices)),
+		&Cell::Scope { id, ref body } => format!("{{{} {}}}", id, highlighted_r(body, &indices[1..])),
+		&Cell::Case { ref condition, ref conclusion } => {
+			if indices[0] == 0 {
+				format!("[=> {} {}]", highlighted_r(condition, &indices[1..]), conclusion)
+			} else {
+				format!("[=> {} {}]", condition, highlighted_r(conclusion, &indices[1..]))
+			}
+		},
+		_ => panic!("highlighted_r: invalid indices - should not happen")
+	}
+}
+
+// canonical text form: #<rule_id>/<indices separated by '.'>, e.g. #12/0.2.1 or #3/ for the rule itself (#3 is accepted too)
+// a handle is written behind the rule_id: #12@7/0.2.1
+impl fmt::Display for CellID {
+	fn fmt(&self, f : &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "#{}", self.rule_id)?;
+		if let Some(handle) = self.handle {
+			write!(f, "@{}", handle.get_id())?;
+		}
+		let strings : Vec<String> = self.indices.iter().map(|x| x.to_string()).collect();
+		write!(f, "/{}", strings.join("."))
+	}
+}
+
+impl fmt::Debug for CellID {
+	fn fmt(&self, f : &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "{}", self)
+	}
+}
+
+impl FromStr for CellID {
+	type Err = SRLError;
+
+	fn from_str(string : &str) -> Result<CellID, SRLError> {
+		let number = |x : &str| -> Result<u64, SRLError> {
+			match x.trim().parse::<u64>() {
+				Ok(x) => Ok(x),
+				Err(_) => Err(SRLError::parse("CellID::from_str", &format!("'{}' is no number", x)))
+			}
+		};
+
+		let string = string.trim();
+		if !string.starts_with('#') {
+			return Err(SRLError::parse("CellID::from_str", "CellID has to start with '#'"));
+		}
+		let (rule_string, indices_string) = match string.find('/') {
+			Some(x) => (&string[1..x], &string[x+1..]),
+			None => (&string[1..], "") // #3 is short for #3/
+		};
+		let cell_id = match rule_string.find('@') {
+			Some(x) => CellID::create(number(&rule_string[..x])? as usize, vec![]).with_handle(RuleHandle::create(number(&rule_string[x+1..])?)),
+			None => CellID::create(number(rule_string)? as usize, vec![])
+		};
+		let mut indices = Vec::new();
+		if !indices_string.trim().is_empty() {
+			for index in indices_string.split('.') {
+				indices.push(number(index)? as usize);
+			}
+		}
+		Ok(CellID { indices : indices, .. cell_id })
+	}
+}
+
+impl fmt::Display for CellPath {
+	fn fmt(&self, f : &mut fmt::Formatter) -> fmt::Result {
+		f.write_str(&self.to_highlighted_string())
+	}
+}
+
+impl fmt::Debug for CellPath {
+	fn fmt(&self, f : &mut fmt::Formatter) -> fmt::Result {
+		f.write_str(&self.to_highlighted_string())
+	}
 }
 
 #[test]
@@ -212,6 +327,16 @@ fn test_cell_id_and_cell_path() {
 	);
 }
 
+#[test]
+fn test_cell_id_siblings() {
+	let rules = vec![simple_by_str("truth"), complex(vec![simple_by_str("truth"), simple_by_str("wot")])];
+	assert_eq!(CellID::create(1, vec![0]).get_right_sibling(&rules).unwrap(), CellID::create(1, vec![1]));
+	assert!(CellID::create(1, vec![1]).get_right_sibling(&rules).is_err());
+	assert!(CellID::create(1, vec![]).get_right_sibling(&rules).is_err());
+	assert!(CellID::create(2, vec![0]).get_right_sibling(&rules).is_err());
+	assert_eq!(CellID::create(1, vec![1]).get_left_sibling().unwrap(), CellID::create(1, vec![0]));
+}
+
 #[test]
 fn test_cell_path_replace_by() {
 	let mut rules : Vec<Cell> = Vec::new();
@@ -223,3 +348,32 @@ fn test_cell_path_replace_by() {
 		complex(vec![simple_by_str("truth"), simple_by_str("wow")])
 	);
 }
+
+#[test]
+fn test_cell_id_text_form() {
+	let cell_id = CellID::create(12, vec![0, 2, 1]);
+	assert_eq!(cell_id.to_string(), "#12/0.2.1");
+	assert_eq!("#12/0.2.1".parse::<CellID>().unwrap(), cell_id);
+	assert_eq!("#3/".parse::<CellID>().unwrap(), CellID::create(3, vec![]));
+
+	let cell_id = CellID::create(2, vec![1]).with_handle(RuleHandle::create(7));
+	assert_eq!(format!("{:?}", cell_id), "#2@7/1");
+	assert_eq!("#2@7/1".parse::<CellID>().unwrap(), cell_id);
+
+	assert!("12/0".parse::<CellID>().is_err());
+	assert_eq!("#12".parse::<CellID>().unwrap(), CellID::create(12, vec![]));
+	assert!("#12/0.x".parse::<CellID>().is_err());
+	assert!("#12/0..1".parse::<CellID>().is_err());
+}
+
+#[test]
+fn test_cell_path_highlighted() {
+	let case = case(simple_by_str("a"), simple_by_str("b"));
+	let rule = scope(0, equals_cell(false_cell(), complex(vec![simple_by_str("p"), var(0), case])));
+	assert_eq!(CellPath::create(rule.clone(), vec![]).unwrap().to_string(), "<{0 (= 'false' (p 0 [=> a b]))}>");
+	assert_eq!(CellPath::create(rule.clone(), vec![0, 2]).unwrap().to_string(), "{0 (= 'false' <(p 0 [=> a b])>)}");
+	assert_eq!(CellPath::create(rule.clone(), vec![0, 2, 2, 1]).unwrap().to_string(), "{0 (= 'false' (p 0 [=> a <b>]))}");
+
+	let rule = complex(vec![simple_by_str("p"), simple_by_str("a")]);
+	assert_eq!(CellPath::create(rule.clone(), vec![1]).unwrap().to_string(), "p <a>");
+}
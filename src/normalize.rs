@@ -1,4 +1,4 @@
-use error::SRLError;
+use error::{SRLError, NormalizationErrorKind};
 use cell::Cell;
 use gen::*;
 
@@ -8,11 +8,11 @@ fn get_new_id(old_id : u32, scope_ids : &Vec<u32>, in_scope_vec : &Vec<bool>) ->
 			if in_scope_vec[index] {
 				return Ok(index as u32);
 			} else {
-				return Err(SRLError("get_new_id".to_string(), format!("already out of scope with id '{}'", old_id)));
+				return Err(SRLError::normalization("get_new_id", NormalizationErrorKind::VarOutOfScope { id : old_id }));
 			}
 		}
 	}
-	return Err(SRLError("get_new_id".to_string(), format!("id '{}' is not in scope_ids '{:?}'", old_id, scope_ids)));
+	return Err(SRLError::normalization("get_new_id", NormalizationErrorKind::VarNotInScope { id : old_id, scope_ids : scope_ids.clone() }));
 }
 
 #[test]
@@ -48,11 +48,11 @@ impl Cell {
 					let norm = cell.get_normalized_from_r(vec, in_scope_vec, from)?;
 					new_cells.push(norm);
 				}
-				return Ok(complex(new_cells));
+				return try_complex(new_cells);
 			}
 			&&Cell::Scope { id : id_out, body : ref body_out } => {
 				if vec.contains(&id_out) {
-					return Err(SRLError("get_normalized_from_r".to_string(), format!("id '{}' used twice", id_out)));
+					return Err(SRLError::normalization("get_normalized_from_r", NormalizationErrorKind::DuplicateScopeId { id : id_out }));
 				}
 
 				vec.push(id_out);
@@ -48,7 +48,7 @@ fn test_trim_tokens() {
 
 fn simple_by_trimmed_tokens(tokens : Vec<String>) -> Result<Cell, SRLError> {
 	if tokens.len() != 1 {
-		return Err(SRLError("simple_by_trimmed_tokens".to_string(), "tokens.len() != 1".to_string()));
+		return Err(SRLError::parse("simple_by_trimmed_tokens", "tokens.len() != 1"));
 	}
 
 	return Ok(try_simple(tokens[0].clone())?);
@@ -60,9 +60,9 @@ fn find_cell_ending(cell_start : usize, tokens : &Vec<String>) -> Option<usize>
 	let mut parens : i32 = 0;
 
 	for index in cell_start..len {
-		if tokens[index] == "(" || tokens[index] == "{" {
+		if tokens[index] == "(" || tokens[index] == "{" || tokens[index] == "[" {
 			parens += 1;
-		} else if tokens[index] == ")" || tokens[index] == "}" {
+		} else if tokens[index] == ")" || tokens[index] == "}" || tokens[index] == "]" {
 			parens -= 1;
 		}
 
@@ -105,13 +105,13 @@ fn scope_by_trimmed_tokens(mut tokens : Vec<String>) -> Result<Cell, SRLError> {
 	// cut { and }
 	let len = tokens.len();
 
-	if len < 3 { return Err(SRLError("scope_by_trimmed_tokens".to_string(), "tokens.len() < 3".to_string())); }
-	if "}" != &tokens.remove(len-1) { return Err(SRLError("scope_by_trimmed_tokens".to_string(), "\"}\" != &tokens.remove(len-1)".to_string())); }
-	if "{" != &tokens.remove(0) { return Err(SRLError("scope_by_trimmed_tokens".to_string(), "\"{\" != &tokens.remove(0)".to_string())); }
+	if len < 3 { return Err(SRLError::parse("scope_by_trimmed_tokens", "tokens.len() < 3")); }
+	if "}" != &tokens.remove(len-1) { return Err(SRLError::parse("scope_by_trimmed_tokens", "\"}\" != &tokens.remove(len-1)")); }
+	if "{" != &tokens.remove(0) { return Err(SRLError::parse("scope_by_trimmed_tokens", "\"{\" != &tokens.remove(0)")); }
 
 	let id = match var_by_trimmed_tokens(vec![tokens.remove(0)]) {
 		Ok(Cell::Var { id : x }) => x,
-		Ok(_) => return Err(SRLError("scope_by_trimmed_tokens".to_string(), "this is not a var cell".to_string())),
+		Ok(_) => return Err(SRLError::parse("scope_by_trimmed_tokens", "this is not a var cell")),
 		Err(srl_error) => return Err(srl_error)
 	};
 	let body = assemble(tokens)?;
@@ -153,14 +153,48 @@ fn test_scope_by_trimmed_tokens() {
 	}
 }
 
+// [=> condition conclusion]
+fn case_by_trimmed_tokens(mut tokens : Vec<String>) -> Result<Cell, SRLError> {
+	let len = tokens.len();
+
+	if len < 5 { return Err(SRLError::parse("case_by_trimmed_tokens", "tokens.len() < 5")); }
+	if "]" != &tokens.remove(len-1) { return Err(SRLError::parse("case_by_trimmed_tokens", "\"]\" != &tokens.remove(len-1)")); }
+	if "[" != &tokens.remove(0) { return Err(SRLError::parse("case_by_trimmed_tokens", "\"[\" != &tokens.remove(0)")); }
+	if "=>" != &tokens.remove(0) { return Err(SRLError::parse("case_by_trimmed_tokens", "case does not start with '=>'")); }
+
+	let ending = match find_cell_ending(0, &tokens) {
+		Some(x) => x,
+		None => return Err(SRLError::parse("case_by_trimmed_tokens", "condition has no ending"))
+	};
+	let conclusion_tokens = tokens.split_off(ending + 1);
+	if conclusion_tokens.is_empty() {
+		return Err(SRLError::parse("case_by_trimmed_tokens", "case has no conclusion"));
+	}
+	if find_cell_ending(0, &conclusion_tokens) != Some(conclusion_tokens.len() - 1) {
+		return Err(SRLError::parse("case_by_trimmed_tokens", "case has more than 2 arguments"));
+	}
+	Ok(case(assemble(tokens)?, assemble(conclusion_tokens)?))
+}
+
+#[test]
+fn test_case_by_trimmed_tokens() {
+	let tokens = |x : Vec<&str>| -> Vec<String> { x.iter().map(|y| y.to_string()).collect() };
+
+	assert_eq!(case(simple_by_str("a"), complex(vec![simple_by_str("b"), simple_by_str("c")])),
+		case_by_trimmed_tokens(tokens(vec!["[", "=>", "a", "(", "b", "c", ")", "]"])).unwrap());
+	assert!(case_by_trimmed_tokens(tokens(vec!["[", "=>", "a", "]"])).is_err());
+	assert!(case_by_trimmed_tokens(tokens(vec!["[", "=>", "a", "b", "c", "]"])).is_err());
+	assert!(case_by_trimmed_tokens(tokens(vec!["[", "a", "b", "c", "]"])).is_err());
+}
+
 fn var_by_trimmed_tokens(tokens : Vec<String>) -> Result<Cell, SRLError> {
 	if tokens.len() != 1 {
-		return Err(SRLError("var_by_trimmed_tokens".to_string(), "tokens.len() != 1".to_string()));
+		return Err(SRLError::parse("var_by_trimmed_tokens", "tokens.len() != 1"));
 	}
 
 	match tokens[0].parse::<u32>() {
 		Ok(x) => return Ok(var(x)),
-		Err(_) => return Err(SRLError("var_by_trimmed_tokens".to_string(), format!("failed parsing of '{}'", tokens[0])))
+		Err(_) => return Err(SRLError::parse("var_by_trimmed_tokens", &format!("failed parsing of '{}'", tokens[0])))
 	};
 }
 
@@ -178,14 +212,14 @@ fn test_var_by_trimmed_tokens() {
 }
 
 // consumes *all* tokens to create one Cell
-// -- used to parse rules, does not accept cases
+// -- used to parse rules
 pub fn assemble(mut tokens : Vec<String>) -> Result<Cell, SRLError> {
 	tokens = trim_tokens(tokens);
 
 	let len = tokens.len();
 
 	if len == 0 {
-		return Err(SRLError("assemble".to_string(), "tokens.len() == 0".to_string()));
+		return Err(SRLError::parse("assemble", "tokens.len() == 0"));
 	} else if tokens.len() == 1 {
 		let token : String = tokens[0].clone();
 		if is_var_token(&token) {
@@ -193,10 +227,12 @@ pub fn assemble(mut tokens : Vec<String>) -> Result<Cell, SRLError> {
 		} else if is_simple_token(&token) {
 			return simple_by_trimmed_tokens(tokens);
 		} else {
-			return Err(SRLError("assemble".to_string(), format!("lone token '{}' is weird", token)));
+			return Err(SRLError::parse("assemble", &format!("lone token '{}' is weird", token)));
 		}
-	} else if tokens[0] == "{" && tokens[len-1] == "}" {
+	} else if tokens[0] == "{" && tokens[len-1] == "}" && find_cell_ending(0, &tokens) == Some(len-1) {
 		return scope_by_trimmed_tokens(tokens);
+	} else if tokens[0] == "[" && tokens[len-1] == "]" && find_cell_ending(0, &tokens) == Some(len-1) {
+		return case_by_trimmed_tokens(tokens);
 	} else {
 		return complex_by_trimmed_tokens(tokens);
 	}
@@ -36,7 +36,7 @@ pub fn tokenize(mut string : String) -> Result<Vec<String>, SRLError> {
 					tmp_string.push(x);
 					state = State::VAR;
 				} else {
-					return Err(SRLError("split_tokens".to_string(), format!("forgot handling for '{}' in NONE state", x)))
+					return Err(SRLError::parse("split_tokens", &format!("forgot handling for '{}' in NONE state", x)))
 				}
 			}
 			(x, &State::VAR) => {
@@ -50,15 +50,15 @@ pub fn tokenize(mut string : String) -> Result<Vec<String>, SRLError> {
 					tmp_string = String::new();
 					state = State::NONE;
 				} else if x == '\'' {
-					return Err(SRLError("split_tokens".to_string(), format!("trying to close var {} with constant tick ' ", tmp_string)));
+					return Err(SRLError::parse("split_tokens", &format!("trying to close var {} with constant tick ' ", tmp_string)));
 				} else if x == '=' {
-					return Err(SRLError("split_tokens".to_string(), format!("trying to close var {} with =", tmp_string)));
+					return Err(SRLError::parse("split_tokens", &format!("trying to close var {} with =", tmp_string)));
 				} else if SIMPLE_CELL_FILL_CHARS.contains(x) {
-					return Err(SRLError("split_tokens".to_string(), format!("trying to close var {} with {}", tmp_string, x)));
+					return Err(SRLError::parse("split_tokens", &format!("trying to close var {} with {}", tmp_string, x)));
 				} else if VAR_FILL_CHARS.contains(x) {
 					tmp_string.push(x);
 				} else {
-					return Err(SRLError("split_tokens".to_string(), format!("forgot handling for '{}' in VAR state", x)))
+					return Err(SRLError::parse("split_tokens", &format!("forgot handling for '{}' in VAR state", x)))
 				}
 			}
 			(x, &State::SIMPLE) => {
@@ -72,15 +72,15 @@ pub fn tokenize(mut string : String) -> Result<Vec<String>, SRLError> {
 					tmp_string = String::new();
 					state = State::NONE;
 				} else if x == '\'' {
-					return Err(SRLError("split_tokens".to_string(), format!("trying to end simple cell \"{}\" with tick '", tmp_string)));
+					return Err(SRLError::parse("split_tokens", &format!("trying to end simple cell \"{}\" with tick '", tmp_string)));
 				} else if x == '=' {
-					return Err(SRLError("split_tokens".to_string(), format!("trying to end simple cell \"{}\" with =", tmp_string)));
+					return Err(SRLError::parse("split_tokens", &format!("trying to end simple cell \"{}\" with =", tmp_string)));
 				} else if SIMPLE_CELL_FILL_CHARS.contains(x) {
 					tmp_string.push(x);
 				} else if VAR_FILL_CHARS.contains(x) {
-					return Err(SRLError("split_tokens".to_string(), format!("trying to end simple cell \"{}\" with {}", tmp_string, x)));
+					return Err(SRLError::parse("split_tokens", &format!("trying to end simple cell \"{}\" with {}", tmp_string, x)));
 				} else {
-					return Err(SRLError("split_tokens".to_string(), format!("forgot handling for '{}' in SIMPLE state", x)))
+					return Err(SRLError::parse("split_tokens", &format!("forgot handling for '{}' in SIMPLE state", x)))
 				}
 			}
 			(x, &State::CONST) => {
@@ -99,13 +99,13 @@ pub fn tokenize(mut string : String) -> Result<Vec<String>, SRLError> {
 					tmp_string = String::new();
 					state = State::AFTER_CELL;
 				} else if x == '=' {
-					return Err(SRLError("split_tokens".to_string(), format!("trying to end const cell \"{}\" with =", tmp_string)));
+					return Err(SRLError::parse("split_tokens", &format!("trying to end const cell \"{}\" with =", tmp_string)));
 				} else if SIMPLE_CELL_FILL_CHARS.contains(x) {
 					tmp_string.push(x);
 				} else if VAR_FILL_CHARS.contains(x) {
-					return Err(SRLError("split_tokens".to_string(), format!("trying to end const cell \"{}\" with {}", tmp_string, x)));
+					return Err(SRLError::parse("split_tokens", &format!("trying to end const cell \"{}\" with {}", tmp_string, x)));
 				} else {
-					return Err(SRLError("split_tokens".to_string(), format!("forgot handling for '{}' in CONST state", x)));
+					return Err(SRLError::parse("split_tokens", &format!("forgot handling for '{}' in CONST state", x)));
 				}
 			},
 			(x, &State::EQ) => {
@@ -116,16 +116,19 @@ pub fn tokenize(mut string : String) -> Result<Vec<String>, SRLError> {
 				} else if x == ' ' {
 					tokens.push("=".to_string());
 					state = State::NONE;
+				} else if x == '>' {
+					tokens.push("=>".to_string());
+					state = State::NONE;
 				} else if x == '\'' {
-					return Err(SRLError("split_tokens".to_string(), "trying to put ' after '='".to_string()));
+					return Err(SRLError::parse("split_tokens", "trying to put ' after '='"));
 				} else if x == '=' {
-					return Err(SRLError("split_tokens".to_string(), "trying to put '=' after '='".to_string()));
+					return Err(SRLError::parse("split_tokens", "trying to put '=' after '='"));
 				} else if SIMPLE_CELL_FILL_CHARS.contains(x) {
-					return Err(SRLError("split_tokens".to_string(), format!("trying to put '{}' after '='", x)));
+					return Err(SRLError::parse("split_tokens", &format!("trying to put '{}' after '='", x)));
 				} else if VAR_FILL_CHARS.contains(x) {
-					return Err(SRLError("split_tokens".to_string(), format!("trying to put '{}' after '='", x)));
+					return Err(SRLError::parse("split_tokens", &format!("trying to put '{}' after '='", x)));
 				} else {
-					return Err(SRLError("split_tokens".to_string(), format!("forgot handling for '{}' in EQ state", x)));
+					return Err(SRLError::parse("split_tokens", &format!("forgot handling for '{}' in EQ state", x)));
 				}
 			}
 			(x, &State::AFTER_CELL) => {
@@ -135,15 +138,15 @@ pub fn tokenize(mut string : String) -> Result<Vec<String>, SRLError> {
 				} else if x == ' ' {
 					state = State::NONE;
 				} else if x == '\'' {
-					return Err(SRLError("split_tokens".to_string(), format!("reading '{}' directly after cell end", x)));
+					return Err(SRLError::parse("split_tokens", &format!("reading '{}' directly after cell end", x)));
 				} else if x == '=' {
-					return Err(SRLError("split_tokens".to_string(), "reading '=' directly after cell end".to_string()));
+					return Err(SRLError::parse("split_tokens", "reading '=' directly after cell end"));
 				} else if SIMPLE_CELL_FILL_CHARS.contains(x) {
-					return Err(SRLError("split_tokens".to_string(), format!("reading '{}' directly after cell end", x)));
+					return Err(SRLError::parse("split_tokens", &format!("reading '{}' directly after cell end", x)));
 				} else if VAR_FILL_CHARS.contains(x) {
-					return Err(SRLError("split_tokens".to_string(), format!("reading '{}' directly after cell end", x)));
+					return Err(SRLError::parse("split_tokens", &format!("reading '{}' directly after cell end", x)));
 				} else {
-					return Err(SRLError("split_tokens".to_string(), format!("forgot handling for '{}' in AFTER_CELL state", x)))
+					return Err(SRLError::parse("split_tokens", &format!("forgot handling for '{}' in AFTER_CELL state", x)))
 				}
 			}
 		}
@@ -157,4 +160,5 @@ fn test_tokenize() {
 	assert_eq!(tokenize("(wow good)".to_string()).unwrap(), vec!["(".to_string(), "wow".to_string(), "good".to_string(), ")".to_string()]);
 	assert_eq!(tokenize("wow".to_string()).unwrap(), vec!["wow".to_string()]);
 	assert_eq!(tokenize("{x}".to_string()).unwrap(), vec!["{".to_string(), "x".to_string(), "}".to_string()]);
+	assert_eq!(tokenize("[=> a b]".to_string()).unwrap(), vec!["[".to_string(), "=>".to_string(), "a".to_string(), "b".to_string(), "]".to_string()]);
 }
@@ -0,0 +1,186 @@
+use cell::Cell;
+use error::SRLError;
+use navi::{CellID, CellPath};
+use db::Database;
+use db::reason::Wrapper;
+use gen::*;
+
+// a cell with wildcards:
+//   _        matches any cell
+//   _bool    matches any bool cell (see CellPath::is_bool)
+// scopes in the pattern match scopes with any id, as long as the vars are used consistently
+#[derive(Clone, PartialEq, Debug)]
+pub struct Pattern {
+	cell : Cell
+}
+
+pub static WILDCARD : &'static str = "_";
+pub static BOOL_WILDCARD : &'static str = "_bool";
+
+// a hit of Database::find
+#[derive(Clone)]
+pub struct Occurrence {
+	cell_id : CellID,
+	wrapper : Option<Wrapper>
+}
+
+impl Occurrence {
+	pub fn get_cell_id(&self) -> CellID { self.cell_id.clone() }
+	// the wrapper ending at the occurrence, if there is one
+	pub fn get_wrapper(&self) -> Option<&Wrapper> { self.wrapper.as_ref() }
+	pub fn is_positive(&self) -> Option<bool> { self.wrapper.as_ref().map(|x| x.is_positive()) }
+	pub fn is_nallq(&self) -> Option<bool> { self.wrapper.as_ref().map(|x| x.is_nallq()) }
+	pub fn is_nexq(&self) -> Option<bool> { self.wrapper.as_ref().map(|x| x.is_nexq()) }
+}
+
+impl Pattern {
+	pub fn create(cell : Cell) -> Pattern {
+		Pattern { cell : cell }
+	}
+
+	pub fn by_string(string : &str) -> Result<Pattern, SRLError> {
+		Ok(Pattern::create(Cell::by_string(string)?))
+	}
+
+	pub fn get_cell(&self) -> Cell { self.cell.clone() }
+
+	// cell is treated as a rule, so _bool matches it on top level
+	pub fn matches(&self, cell : &Cell) -> bool {
+		self.matches_at(cell, &[])
+	}
+
+	// matches the subcell of root at indices, _bool respects the position in root
+	pub fn matches_at(&self, root : &Cell, indices : &[usize]) -> bool {
+		let cell = match root.path_ref(indices) {
+			Some(x) => x,
+			None => return false
+		};
+		let mut matcher = Matcher { root : root, indices : indices.to_vec(), bindings : vec![] };
+		matcher.matches_r(&self.cell, cell)
+	}
+}
+
+struct Matcher<'a> {
+	root : &'a Cell,
+	indices : Vec<usize>, // position of the current cell in root
+	bindings : Vec<(u32, u32)> // (pattern scope id, cell scope id) of all scopes around the current position
+}
+
+impl<'a> Matcher<'a> {
+	fn is_bool(&self) -> bool {
+		match CellPath::create(self.root.clone(), self.indices.clone()) {
+			Ok(x) => x.is_bool(),
+			Err(_) => false
+		}
+	}
+
+	fn matches_child(&mut self, index : usize, pattern : &Cell, cell : &Cell) -> bool {
+		self.indices.push(index);
+		let result = self.matches_r(pattern, cell);
+		self.indices.pop();
+		result
+	}
+
+	fn matches_r(&mut self, pattern : &Cell, cell : &Cell) -> bool {
+		if *pattern == simple_by_str(WILDCARD) {
+			return true;
+		}
+		if *pattern == simple_by_str(BOOL_WILDCARD) {
+			return self.is_bool();
+		}
+		match (pattern, cell) {
+			(&Cell::Simple { string : ref s1 }, &Cell::Simple { string : ref s2 }) => s1 == s2,
+			(&Cell::Complex { cells : ref c1 }, &Cell::Complex { cells : ref c2 }) => {
+				c1.len() == c2.len() && (0..c1.len()).all(|i| self.matches_child(i, &c1[i], &c2[i]))
+			},
+			(&Cell::Scope { id : id1, body : ref b1 }, &Cell::Scope { id : id2, body : ref b2 }) => {
+				self.bindings.push((id1, id2));
+				let result = self.matches_child(0, b1, b2);
+				self.bindings.pop();
+				result
+			},
+			(&Cell::Var { id : id1 }, &Cell::Var { id : id2 }) => {
+				let b1 = self.bindings.iter().rposition(|x| x.0 == id1);
+				let b2 = self.bindings.iter().rposition(|x| x.1 == id2);
+				match (b1, b2) {
+					(None, None) => id1 == id2, // free vars have to be equal
+					(x, y) => x == y
+				}
+			},
+			(&Cell::Case { condition : ref cond1, conclusion : ref conc1 }, &Cell::Case { condition : ref cond2, conclusion : ref conc2 }) => {
+				self.matches_child(0, cond1, cond2) && self.matches_child(1, conc1, conc2)
+			},
+			_ => false
+		}
+	}
+}
+
+impl Database {
+	// every cell of every rule matching the pattern, in rule order and pre-order within the rules
+	pub fn find(&self, pattern : &Pattern) -> Vec<Occurrence> {
+		let mut occurrences = Vec::new();
+		for rule_id in 0..self.count_rules() {
+			let rule = match self.get_rule_ref(rule_id) {
+				Some(x) => x,
+				None => continue
+			};
+			for (_, indices) in rule.pre_order() {
+				if !pattern.matches_at(rule, &indices) {
+					continue;
+				}
+				let wrapper = match CellPath::create(rule.clone(), indices.clone()) {
+					Ok(path) => path.get_wrapper(),
+					Err(_) => None
+				};
+				occurrences.push(Occurrence { cell_id : CellID::create(rule_id, indices), wrapper : wrapper });
+			}
+		}
+		occurrences
+	}
+}
+
+#[test]
+fn test_pattern() {
+	let pattern = Pattern::by_string("= 'false' _").unwrap();
+	assert!(pattern.matches(&equals_cell(false_cell(), simple_by_str("x"))));
+	assert!(pattern.matches(&equals_cell(false_cell(), scope(0, var(0)))));
+	assert!(!pattern.matches(&equals_cell(true_cell(), simple_by_str("x"))));
+	assert!(!pattern.matches(&simple_by_str("x")));
+
+	// {0 (p 0 1)} against {3 (p 3 1)}
+	let pattern = Pattern::create(scope(0, complex(vec![simple_by_str("p"), var(0), var(1)])));
+	assert!(pattern.matches(&scope(3, complex(vec![simple_by_str("p"), var(3), var(1)]))));
+	assert!(!pattern.matches(&scope(3, complex(vec![simple_by_str("p"), var(1), var(1)]))));
+	assert!(!pattern.matches(&scope(1, complex(vec![simple_by_str("p"), var(1), var(1)]))));
+}
+
+#[test]
+fn test_bool_wildcard() {
+	let pattern = Pattern::by_string("= 'false' _bool").unwrap();
+	assert!(pattern.matches(&equals_cell(false_cell(), equals_cell(simple_by_str("a"), simple_by_str("b")))));
+	assert!(!pattern.matches(&equals_cell(false_cell(), simple_by_str("x"))));
+
+	// x is bool as body of a scope
+	let root = scope(0, equals_cell(false_cell(), scope(1, simple_by_str("x"))));
+	let pattern = Pattern::by_string("{0 _bool}").unwrap();
+	assert!(pattern.matches_at(&root, &[0, 2]));
+}
+
+#[test]
+fn test_find() {
+	let db = Database::by_string("= 'false' {0 (= 'false' (p 0))}. {0 (p 0)}. (p a).").unwrap();
+	let occurrences = db.find(&Pattern::by_string("{0 (p 0)}").unwrap());
+	assert_eq!(occurrences.iter().map(|x| x.get_cell_id()).collect::<Vec<_>>(), vec![CellID::create(2, vec![])]);
+
+	let occurrences = db.find(&Pattern::by_string("p _").unwrap());
+	let ids : Vec<String> = occurrences.iter().map(|x| x.get_cell_id().to_string()).collect();
+	assert_eq!(ids, vec!["#1/2.0.2", "#2/0", "#3/"]);
+	assert_eq!(occurrences[0].is_positive(), Some(true));
+	assert_eq!(occurrences[0].is_nexq(), Some(false));
+	assert_eq!(occurrences[0].is_nallq(), Some(true));
+	assert_eq!(occurrences[1].is_positive(), Some(true));
+	assert_eq!(occurrences[1].is_nallq(), Some(false));
+	assert_eq!(occurrences[2].is_positive(), Some(true));
+
+	assert_eq!(db.find(&Pattern::by_string("= 'false' _bool").unwrap()).len(), 1); // (p 0) is no bool-cell
+}
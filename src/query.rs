@@ -0,0 +1,288 @@
+use std::collections::HashSet;
+use cell::{Cell, CellType};
+use error::{SRLError, NavigationErrorKind};
+use navi::CellID;
+use pattern::Pattern;
+use db::Database;
+
+// a small query language for selecting cells of a database:
+//
+//   3:0.2.1                 the cell at indices [0, 2, 1] of rule 3
+//   3                       the rule 3 itself
+//   *:0                     the first subcell of every rule
+//   3:0.*.1                 * matches every subcell, ** every descendant (including itself)
+//   rule 3 // (= 'false' _) every cell in rule 3 matching the pattern (see pattern.rs)
+//   3:0 // @scope           every scope below 3:0; @simple, @complex, @scope, @var, @case
+//   // p                    every occurence of the simple cell p
+
+#[derive(Clone, PartialEq, Debug)]
+enum RuleSelector { All, Index(usize) }
+
+#[derive(Clone, PartialEq, Debug)]
+enum Step { Index(usize), Any, Descendants }
+
+#[derive(Clone, PartialEq, Debug)]
+enum Filter { Type(CellType), Pattern(Pattern) }
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct Query {
+	rules : RuleSelector,
+	steps : Vec<Step>,
+	filter : Option<Filter>
+}
+
+// everything that can be used to point at a cell in a law
+pub trait CellLocator {
+	fn locate(&self, db : &Database) -> Result<CellID, SRLError>;
+}
+
+impl CellLocator for CellID {
+	fn locate(&self, db : &Database) -> Result<CellID, SRLError> {
+		match self.get_handle() {
+			Some(handle) => Ok(CellID::create(db.get_rule_index(handle)?, self.get_indices()).with_handle(handle)),
+			None => Ok(self.clone())
+		}
+	}
+}
+
+impl CellLocator for Query {
+	fn locate(&self, db : &Database) -> Result<CellID, SRLError> {
+		self.find_one(db)
+	}
+}
+
+impl<'a> CellLocator for &'a str {
+	fn locate(&self, db : &Database) -> Result<CellID, SRLError> {
+		Query::parse(self)?.find_one(db)
+	}
+}
+
+impl<'a, T : CellLocator> CellLocator for &'a T {
+	fn locate(&self, db : &Database) -> Result<CellID, SRLError> {
+		(*self).locate(db)
+	}
+}
+
+fn parse_number(string : &str) -> Result<usize, SRLError> {
+	match string.parse::<usize>() {
+		Ok(x) => Ok(x),
+		Err(_) => Err(SRLError::parse("Query::parse", &format!("'{}' is no number", string)))
+	}
+}
+
+fn parse_filter(string : &str) -> Result<Filter, SRLError> {
+	if string.starts_with('@') {
+		let cell_type = match &string[1..] {
+			"simple" => CellType::Simple,
+			"complex" => CellType::Complex,
+			"scope" => CellType::Scope,
+			"var" => CellType::Var,
+			"case" => CellType::Case,
+			x => return Err(SRLError::parse("Query::parse", &format!("unknown cell type '{}'", x)))
+		};
+		return Ok(Filter::Type(cell_type));
+	}
+	Ok(Filter::Pattern(Pattern::by_string(string)?))
+}
+
+impl Query {
+	pub fn parse(string : &str) -> Result<Query, SRLError> {
+		let mut string = string.trim();
+		if string.starts_with("rule ") {
+			string = string[5..].trim_start();
+		}
+
+		let (location, filter) = match string.find("//") {
+			Some(x) => (string[..x].trim(), Some(parse_filter(string[x+2..].trim())?)),
+			None => (string, None)
+		};
+
+		let (rules_string, steps_string) = match location.find(':') {
+			Some(x) => (location[..x].trim(), Some(location[x+1..].trim())),
+			None => (location, None)
+		};
+
+		let rules = match rules_string {
+			"" => {
+				if filter.is_none() {
+					return Err(SRLError::parse("Query::parse", "empty query"));
+				}
+				RuleSelector::All
+			},
+			"*" => RuleSelector::All,
+			x => RuleSelector::Index(parse_number(x)?)
+		};
+
+		let mut steps = Vec::new();
+		if let Some(steps_string) = steps_string {
+			for step in steps_string.split('.') {
+				steps.push(match step.trim() {
+					"*" => Step::Any,
+					"**" => Step::Descendants,
+					x => Step::Index(parse_number(x)?)
+				});
+			}
+		}
+
+		Ok(Query { rules : rules, steps : steps, filter : filter })
+	}
+
+	pub fn find_all(&self, db : &Database) -> Vec<CellID> {
+		let rule_ids : Vec<usize> = match self.rules {
+			RuleSelector::All => (0..db.count_rules()).collect(),
+			RuleSelector::Index(x) => vec![x]
+		};
+
+		let mut result = Vec::new();
+		for rule_id in rule_ids {
+			let rule = match db.get_rule_ref(rule_id) {
+				Some(x) => x,
+				None => continue
+			};
+			// find_in_rule has no duplicates
+			for indices in self.find_in_rule(rule) {
+				result.push(CellID::create(rule_id, indices));
+			}
+		}
+		result
+	}
+
+	fn find_in_rule(&self, rule : &Cell) -> Vec<Vec<usize>> {
+		let mut current : Vec<Vec<usize>> = vec![vec![]];
+		for step in &self.steps {
+			if let &Step::Descendants = step {
+				current = descendants(rule, current).into_iter().map(|(_, x)| x).collect();
+				continue;
+			}
+			let mut seen = HashSet::new();
+			let mut next = Vec::new();
+			for indices in current {
+				let cell = match rule.path_ref(&indices) {
+					Some(x) => x,
+					None => continue
+				};
+				match step {
+					&Step::Index(index) => {
+						if index < cell.count_subcells() && seen.insert(child(&indices, index)) {
+							next.push(child(&indices, index));
+						}
+					},
+					&Step::Any => {
+						for index in 0..cell.count_subcells() {
+							if seen.insert(child(&indices, index)) {
+								next.push(child(&indices, index));
+							}
+						}
+					},
+					&Step::Descendants => unreachable!()
+				}
+			}
+			current = next;
+		}
+
+		let filter = match self.filter {
+			Some(ref x) => x,
+			None => return current
+		};
+		descendants(rule, current).into_iter()
+			.filter(|&(sub_cell, ref indices)| match filter {
+				&Filter::Type(ref cell_type) => sub_cell.get_type() == *cell_type,
+				&Filter::Pattern(ref pattern) => pattern.matches_at(rule, indices)
+			})
+			.map(|(_, indices)| indices)
+			.collect()
+	}
+
+	// errors unless exactly one cell matches
+	pub fn find_one(&self, db : &Database) -> Result<CellID, SRLError> {
+		let mut all = self.find_all(db);
+		match all.len() {
+			0 => Err(SRLError::navigation("Query::find_one", NavigationErrorKind::QueryNoMatch)),
+			1 => Ok(all.remove(0)),
+			x => Err(SRLError::navigation("Query::find_one", NavigationErrorKind::QueryAmbiguous { count : x }))
+		}
+	}
+}
+
+// the pre-order walks of all roots one after another, without repeating a cell: every cell is generated once
+fn descendants<'a>(rule : &'a Cell, roots : Vec<Vec<usize>>) -> Vec<(&'a Cell, Vec<usize>)> {
+	let mut walked : HashSet<Vec<usize>> = HashSet::new();
+	let mut result = Vec::new();
+	for indices in roots {
+		// a root inside of a root walked before is part of its walk already
+		if (0..indices.len() + 1).any(|x| walked.contains(&indices[..x])) {
+			continue;
+		}
+		let cell = match rule.path_ref(&indices) {
+			Some(x) => x,
+			None => continue
+		};
+		let mut iter = cell.pre_order();
+		while let Some(item) = iter.next() {
+			let mut vec = indices.clone();
+			vec.extend(item.1.iter().cloned());
+			if !item.1.is_empty() && walked.contains(&vec) {
+				iter.skip_children(); // walked before as a root of its own
+				continue;
+			}
+			result.push((item.0, vec));
+		}
+		walked.insert(indices);
+	}
+	result
+}
+
+fn child(indices : &Vec<usize>, index : usize) -> Vec<usize> {
+	let mut vec = indices.clone();
+	vec.push(index);
+	vec
+}
+
+impl Database {
+	pub fn query(&self, query : &str) -> Result<Vec<CellID>, SRLError> {
+		Ok(Query::parse(query)?.find_all(self))
+	}
+
+	pub fn locate<L : CellLocator>(&self, locator : L) -> Result<CellID, SRLError> {
+		locator.locate(self)
+	}
+}
+
+#[test]
+fn test_query_parse() {
+	assert!(Query::parse("3:0.2.1").is_ok());
+	assert!(Query::parse("rule 3 // (= 'false' _)").is_ok());
+	assert!(Query::parse("// @scope").is_ok());
+	assert!(Query::parse("").is_err());
+	assert!(Query::parse("3:0.x").is_err());
+	assert!(Query::parse("3 // @nothing").is_err());
+}
+
+#[test]
+fn test_query() {
+	let db = Database::by_string("= 'false' (p a). {0 (= 'false' (p 0))}. p b.").unwrap();
+
+	assert_eq!(db.query("1:2.1").unwrap(), vec![CellID::create(1, vec![2, 1])]);
+	assert_eq!(db.query("1:2.5").unwrap(), vec![]);
+	assert_eq!(db.query("*:0").unwrap(), vec![CellID::create(0, vec![0]), CellID::create(1, vec![0]), CellID::create(2, vec![0]), CellID::create(3, vec![0])]);
+	assert_eq!(db.query("2:0.*").unwrap().len(), 3);
+	assert_eq!(db.query("rule 2 // (= 'false' _)").unwrap(), vec![CellID::create(2, vec![0])]);
+	assert_eq!(db.query("// (= 'false' _)").unwrap().len(), 2);
+	assert_eq!(db.query("// p").unwrap().len(), 3);
+	assert_eq!(db.query("* // @scope").unwrap(), vec![CellID::create(0, vec![]), CellID::create(2, vec![])]);
+	assert_eq!(db.query("0:** // @var").unwrap(), vec![CellID::create(0, vec![0, 1]), CellID::create(0, vec![0, 2])]);
+	assert_eq!(db.query("2:0.** // @var").unwrap(), vec![CellID::create(2, vec![0, 2, 1])]);
+
+	assert_eq!(db.locate("3 // b").unwrap(), CellID::create(3, vec![1]));
+	assert!(db.locate("// p").is_err());
+	assert!(db.locate("// q").is_err());
+}
+
+#[test]
+fn test_query_no_duplicates() {
+	let db = Database::by_string("{0 (= 'false' (p 0 (q 0)))}.").unwrap();
+	let count = db.get_rule_ref(1).unwrap().pre_order().count();
+	assert_eq!(db.query("1:**.**").unwrap().len(), count);
+	assert_eq!(db.query("1:**.** // @var").unwrap(), vec![CellID::create(1, vec![0, 2, 1]), CellID::create(1, vec![0, 2, 2, 1])]);
+	assert_eq!(db.query("1:**.*.** // q").unwrap(), vec![CellID::create(1, vec![0, 2, 2, 0])]);
+}
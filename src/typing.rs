@@ -0,0 +1,332 @@
+use std::collections::HashMap;
+use std::fmt;
+use cell::Cell;
+use navi::CellID;
+use db::Database;
+use gen::*;
+
+// whole-rule analysis of which cells have to be bool-cells.
+// in contrast to CellPath::is_bool, which only looks at the parent, the facts are
+// propagated through equalities, bound vars and symbols occuring in other rules.
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum BoolType { Bool, NonBool, Unknown }
+
+#[derive(Clone, PartialEq, Debug)]
+pub enum BoolReason {
+	Rule, // the root of a rule
+	TruthConstant, // 'true' or 'false'
+	OtherConstant, // constants other than 'true' and 'false' are never bools
+	EqualsCell, // (= a b)
+	EqualsSymbol, // the = in (= a b)
+	Scope,
+	ScopeBody,
+	CaseCondition,
+	CaseConclusion,
+	Equality { other : CellID }, // (= a b): a has the same type as b
+	Var { other : CellID }, // another occurence of the same bound var
+	Symbol { other : CellID } // the same closed cell at another location
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct BoolAnnotation {
+	bool_type : BoolType,
+	reason : Option<BoolReason> // None iff bool_type is Unknown
+}
+
+// a cell, that is proven to be a bool-cell and no bool-cell
+#[derive(Clone, PartialEq, Debug)]
+pub struct BoolConflict {
+	cell_id : CellID,
+	bool_reason : BoolReason,
+	non_bool_reason : BoolReason
+}
+
+pub struct BoolAnalysis {
+	annotations : HashMap<(usize, Vec<usize>), BoolAnnotation>,
+	conflicts : Vec<BoolConflict>
+}
+
+impl BoolAnnotation {
+	pub fn get_bool_type(&self) -> BoolType { self.bool_type }
+	pub fn get_reason(&self) -> Option<BoolReason> { self.reason.clone() }
+}
+
+impl BoolConflict {
+	pub fn get_cell_id(&self) -> CellID { self.cell_id.clone() }
+	pub fn get_bool_reason(&self) -> BoolReason { self.bool_reason.clone() }
+	pub fn get_non_bool_reason(&self) -> BoolReason { self.non_bool_reason.clone() }
+}
+
+impl fmt::Display for BoolReason {
+	fn fmt(&self, f : &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			&BoolReason::Rule => f.write_str("rules are bool-cells"),
+			&BoolReason::TruthConstant => f.write_str("'true' and 'false' are bool-cells"),
+			&BoolReason::OtherConstant => f.write_str("constants other than 'true' and 'false' are no bool-cells"),
+			&BoolReason::EqualsCell => f.write_str("equals cells are bool-cells"),
+			&BoolReason::EqualsSymbol => f.write_str("= is no bool-cell"),
+			&BoolReason::Scope => f.write_str("scopes are bool-cells"),
+			&BoolReason::ScopeBody => f.write_str("the body of a scope is a bool-cell"),
+			&BoolReason::CaseCondition => f.write_str("the condition of a case-cell is a bool-cell"),
+			&BoolReason::CaseConclusion => f.write_str("the conclusion of a case-cell is a bool-cell"),
+			&BoolReason::Equality { ref other } => write!(f, "it is compared with rule {} at {:?}", other.get_rule_id(), other.get_indices()),
+			&BoolReason::Var { ref other } => write!(f, "the same var occurs in rule {} at {:?}", other.get_rule_id(), other.get_indices()),
+			&BoolReason::Symbol { ref other } => write!(f, "the same cell occurs in rule {} at {:?}", other.get_rule_id(), other.get_indices())
+		}
+	}
+}
+
+struct Node {
+	rule_id : usize,
+	indices : Vec<usize>,
+	key : Option<String> // cells with the same key always have the same value
+}
+
+// bound vars are keyed by their scope, closed cells by their string representation
+fn find_key(rule_id : usize, cell : &Cell, scopes : &Vec<(u32, Vec<usize>)>) -> Option<String> {
+	if let &Cell::Var { id : id_out } = cell {
+		return scopes.iter().rev().find(|x| x.0 == id_out).map(|x| format!("{}/{:?}", rule_id, x.1));
+	}
+	if cell.pre_order().any(|(x, _)| if let &Cell::Var {..} = x { true } else { false }) {
+		return None;
+	}
+	Some(cell.to_string())
+}
+
+fn collect_nodes(rule_id : usize, cell : &Cell, indices : &mut Vec<usize>, scopes : &mut Vec<(u32, Vec<usize>)>, nodes : &mut Vec<Node>) {
+	nodes.push(Node { rule_id : rule_id, indices : indices.clone(), key : find_key(rule_id, cell, scopes) });
+	if let &Cell::Scope { id : id_out, .. } = cell {
+		scopes.push((id_out, indices.clone()));
+	}
+	for index in 0..cell.count_subcells() {
+		if let Some(child) = cell.get_subcell_ref(index) {
+			indices.push(index);
+			collect_nodes(rule_id, child, indices, scopes, nodes);
+			indices.pop();
+		}
+	}
+	if let &Cell::Scope {..} = cell {
+		scopes.pop();
+	}
+}
+
+impl BoolAnalysis {
+	pub fn create(rules : &[Cell]) -> BoolAnalysis {
+		let mut analysis = BoolAnalysis { annotations : HashMap::new(), conflicts : Vec::new() };
+
+		let mut nodes = Vec::new();
+		for (rule_id, rule) in rules.iter().enumerate() {
+			collect_nodes(rule_id, rule, &mut vec![], &mut vec![], &mut nodes);
+		}
+
+		// local facts
+		for node in &nodes {
+			let cell = match rules[node.rule_id].path_ref(&node.indices) {
+				Some(x) => x,
+				None => continue
+			};
+			if node.indices.is_empty() {
+				analysis.set(node.rule_id, &node.indices, BoolType::Bool, BoolReason::Rule);
+			}
+			if *cell == true_cell() || *cell == false_cell() {
+				analysis.set(node.rule_id, &node.indices, BoolType::Bool, BoolReason::TruthConstant);
+			} else if cell.is_constant() {
+				analysis.set(node.rule_id, &node.indices, BoolType::NonBool, BoolReason::OtherConstant);
+			}
+			match cell {
+				&Cell::Scope {..} => {
+					analysis.set(node.rule_id, &node.indices, BoolType::Bool, BoolReason::Scope);
+					analysis.set(node.rule_id, &child(&node.indices, 0), BoolType::Bool, BoolReason::ScopeBody);
+				},
+				&Cell::Case {..} => {
+					analysis.set(node.rule_id, &child(&node.indices, 0), BoolType::Bool, BoolReason::CaseCondition);
+					analysis.set(node.rule_id, &child(&node.indices, 1), BoolType::Bool, BoolReason::CaseConclusion);
+				},
+				&Cell::Complex {..} => {
+					if let Ok(_) = cell.get_equals_cell_arguments() {
+						analysis.set(node.rule_id, &node.indices, BoolType::Bool, BoolReason::EqualsCell);
+						analysis.set(node.rule_id, &child(&node.indices, 0), BoolType::NonBool, BoolReason::EqualsSymbol);
+					}
+				},
+				_ => {}
+			}
+		}
+
+		let equals_nodes : Vec<&Node> = nodes.iter()
+			.filter(|x| rules[x.rule_id].path_ref(&x.indices).map(|c| c.get_equals_cell_arguments().is_ok()).unwrap_or(false))
+			.collect();
+
+		let mut keyed : HashMap<&String, Vec<&Node>> = HashMap::new();
+		for node in &nodes {
+			if let Some(ref key) = node.key {
+				keyed.entry(key).or_insert_with(Vec::new).push(node);
+			}
+		}
+
+		// propagate until nothing changes
+		loop {
+			let mut changed = false;
+			for node in &equals_nodes {
+				let a = child(&node.indices, 1);
+				let b = child(&node.indices, 2);
+				changed |= analysis.copy(node.rule_id, &a, &b, |other| BoolReason::Equality { other : other });
+				changed |= analysis.copy(node.rule_id, &b, &a, |other| BoolReason::Equality { other : other });
+			}
+			for group in keyed.values() {
+				let known = group.iter().find(|x| analysis.get_type(x.rule_id, &x.indices) != BoolType::Unknown);
+				if let Some(known) = known {
+					let bool_type = analysis.get_type(known.rule_id, &known.indices);
+					let other = CellID::create(known.rule_id, known.indices.clone());
+					for node in group {
+						let reason = match rules[node.rule_id].path_ref(&node.indices) {
+							Some(&Cell::Var {..}) => BoolReason::Var { other : other.clone() },
+							_ => BoolReason::Symbol { other : other.clone() }
+						};
+						changed |= analysis.get_type(node.rule_id, &node.indices) == BoolType::Unknown;
+						analysis.set(node.rule_id, &node.indices, bool_type, reason); // a known other type is a conflict
+					}
+				}
+			}
+			if !changed {
+				break;
+			}
+		}
+		analysis
+	}
+
+	// the first fact found for a cell is kept, a fact of the other type is reported as conflict (once per cell)
+	fn set(&mut self, rule_id : usize, indices : &Vec<usize>, bool_type : BoolType, reason : BoolReason) {
+		let first = self.annotations.entry((rule_id, indices.clone())).or_insert(BoolAnnotation { bool_type : bool_type, reason : Some(reason.clone()) }).clone();
+		if first.bool_type == bool_type {
+			return;
+		}
+		let cell_id = CellID::create(rule_id, indices.clone());
+		if self.conflicts.iter().any(|x| x.cell_id == cell_id) {
+			return;
+		}
+		let first_reason = match first.reason {
+			Some(x) => x,
+			None => panic!("BoolAnalysis::set: annotation without reason - should not happen")
+		};
+		let (bool_reason, non_bool_reason) = match bool_type {
+			BoolType::Bool => (reason, first_reason),
+			_ => (first_reason, reason)
+		};
+		self.conflicts.push(BoolConflict { cell_id : cell_id, bool_reason : bool_reason, non_bool_reason : non_bool_reason });
+	}
+
+	// copies the type of from to to, if only from is known; returns whether to was unknown before
+	fn copy<F>(&mut self, rule_id : usize, from : &Vec<usize>, to : &Vec<usize>, reason : F) -> bool where F : Fn(CellID) -> BoolReason {
+		let bool_type = self.get_type(rule_id, from);
+		if bool_type == BoolType::Unknown {
+			return false;
+		}
+		let changed = self.get_type(rule_id, to) == BoolType::Unknown;
+		self.set(rule_id, to, bool_type, reason(CellID::create(rule_id, from.clone())));
+		changed
+	}
+
+	fn get_type(&self, rule_id : usize, indices : &Vec<usize>) -> BoolType {
+		match self.annotations.get(&(rule_id, indices.clone())) {
+			Some(x) => x.bool_type,
+			None => BoolType::Unknown
+		}
+	}
+
+	pub fn get_annotation(&self, cell_id : &CellID) -> BoolAnnotation {
+		match self.annotations.get(&(cell_id.get_rule_id(), cell_id.get_indices())) {
+			Some(x) => x.clone(),
+			None => BoolAnnotation { bool_type : BoolType::Unknown, reason : None }
+		}
+	}
+
+	pub fn get_bool_type(&self, cell_id : &CellID) -> BoolType {
+		self.get_annotation(cell_id).bool_type
+	}
+
+	// the cells, that are proven to be bool-cells and no bool-cells (the rules contradict each other)
+	pub fn get_conflicts(&self) -> &[BoolConflict] {
+		&self.conflicts
+	}
+
+	// human readable explanation, e.g. for error messages
+	pub fn explain(&self, cell_id : &CellID) -> String {
+		if let Some(conflict) = self.conflicts.iter().find(|x| x.cell_id == *cell_id) {
+			return format!("bool-cell, because {}, but no bool-cell, because {}", conflict.bool_reason, conflict.non_bool_reason);
+		}
+		let annotation = self.get_annotation(cell_id);
+		match (annotation.bool_type, annotation.reason) {
+			(BoolType::Bool, Some(reason)) => format!("bool-cell, because {}", reason),
+			(BoolType::NonBool, Some(reason)) => format!("no bool-cell, because {}", reason),
+			_ => "unknown whether this is a bool-cell".to_string()
+		}
+	}
+}
+
+fn child(indices : &Vec<usize>, index : usize) -> Vec<usize> {
+	let mut vec = indices.clone();
+	vec.push(index);
+	vec
+}
+
+impl Cell {
+	// analyses self as if it was the only rule, the CellIDs in the result use rule_id 0
+	pub fn get_bool_analysis(&self) -> BoolAnalysis {
+		BoolAnalysis::create(&[self.clone()])
+	}
+}
+
+impl Database {
+	pub fn get_bool_analysis(&self) -> BoolAnalysis {
+		BoolAnalysis::create(&self.get_rules())
+	}
+}
+
+#[test]
+fn test_bool_analysis_local() {
+	// {0 (= 'true' (p 0 x))}
+	let cell = scope(0, equals_cell(true_cell(), complex(vec![simple_by_str("p"), var(0), simple_by_str("x")])));
+	let analysis = cell.get_bool_analysis();
+	let at = |indices : Vec<usize>| analysis.get_annotation(&CellID::create(0, indices));
+
+	assert_eq!(at(vec![]), BoolAnnotation { bool_type : BoolType::Bool, reason : Some(BoolReason::Rule) });
+	assert_eq!(at(vec![0]).get_reason(), Some(BoolReason::ScopeBody));
+	assert_eq!(at(vec![0, 0]).get_bool_type(), BoolType::NonBool);
+	assert_eq!(at(vec![0, 2]), BoolAnnotation { bool_type : BoolType::Bool, reason : Some(BoolReason::Equality { other : CellID::create(0, vec![0, 1]) }) });
+	assert_eq!(at(vec![0, 2, 1]).get_bool_type(), BoolType::Unknown);
+}
+
+#[test]
+fn test_bool_analysis_database() {
+	let db = Database::by_string("= 'true' a. p a b. {0 (= (f 0) 'x')}.").unwrap();
+	let analysis = db.get_bool_analysis();
+
+	// a is bool in rule 1, so it is in rule 2
+	assert_eq!(analysis.get_annotation(&CellID::create(2, vec![1])), BoolAnnotation { bool_type : BoolType::Bool, reason : Some(BoolReason::Symbol { other : CellID::create(1, vec![2]) }) });
+	assert_eq!(analysis.get_bool_type(&CellID::create(2, vec![2])), BoolType::Unknown);
+	// (f 0) is compared with a non-bool constant
+	assert_eq!(analysis.get_bool_type(&CellID::create(3, vec![0, 1])), BoolType::NonBool);
+
+	// {0 [=> 0 (q 0)]}: 0 is the condition of a case, so it is bool inside of (q 0) too
+	let analysis = scope(0, case(var(0), complex(vec![simple_by_str("q"), var(0)]))).get_bool_analysis();
+	assert_eq!(analysis.get_annotation(&CellID::create(0, vec![0, 1, 1])).get_reason(), Some(BoolReason::Var { other : CellID::create(0, vec![0, 0]) }));
+	assert_eq!(analysis.explain(&CellID::create(0, vec![0, 0])), "bool-cell, because the condition of a case-cell is a bool-cell");
+}
+
+#[test]
+fn test_bool_analysis_conflicts() {
+	assert!(Database::by_string("= 'true' a. p a b.").unwrap().get_bool_analysis().get_conflicts().is_empty());
+
+	// a is bool in rule 1 and compared with a non-bool constant in rule 2
+	let db = Database::by_string("= 'true' a. = a 'x'.").unwrap();
+	let analysis = db.get_bool_analysis();
+	let conflicts = analysis.get_conflicts();
+	assert_eq!(conflicts.len(), 1);
+	assert_eq!(conflicts[0].get_cell_id(), CellID::create(2, vec![1]));
+	assert_eq!(conflicts[0].get_bool_reason(), BoolReason::Symbol { other : CellID::create(1, vec![2]) });
+	assert_eq!(conflicts[0].get_non_bool_reason(), BoolReason::Equality { other : CellID::create(2, vec![2]) });
+	assert!(analysis.explain(&CellID::create(2, vec![1])).contains("but no bool-cell"));
+	// the first fact is kept
+	assert_eq!(analysis.get_bool_type(&CellID::create(2, vec![1])), BoolType::NonBool);
+}
@@ -0,0 +1,184 @@
+use std::fmt;
+use cell::{Cell, SimpleString};
+use navi::CellPath;
+use db::Database;
+use gen::*;
+
+#[derive(Clone, PartialEq, Debug)]
+pub enum ViolationKind {
+	UnboundVar { id : u32 },
+	DuplicateScopeId { id : u32 },
+	ShortComplex { len : usize }, // complex cells need at least 2 subcells, see gen::complex
+	InvalidSimpleString { string : String },
+	NonBoolRule // the root of a rule can never be a bool
+}
+
+#[derive(Clone, PartialEq)]
+pub struct Violation {
+	path : CellPath,
+	kind : ViolationKind
+}
+
+impl Violation {
+	pub fn get_path(&self) -> CellPath { self.path.clone() }
+	pub fn get_kind(&self) -> ViolationKind { self.kind.clone() }
+}
+
+impl fmt::Display for ViolationKind {
+	fn fmt(&self, f : &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			&ViolationKind::UnboundVar { id } => write!(f, "var '{}' is not bound by a surrounding scope", id),
+			&ViolationKind::DuplicateScopeId { id } => write!(f, "id '{}' used twice", id),
+			&ViolationKind::ShortComplex { len } => write!(f, "complex cell has {} subcells, needs more than 1", len),
+			&ViolationKind::InvalidSimpleString { ref string } => write!(f, "invalid simple string '{}'", string),
+			&ViolationKind::NonBoolRule => f.write_str("rule is not a bool-cell")
+		}
+	}
+}
+
+impl fmt::Display for Violation {
+	fn fmt(&self, f : &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "{:?}: {}", self.path.get_indices(), self.kind)
+	}
+}
+
+impl fmt::Debug for Violation {
+	fn fmt(&self, f : &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "{}", self)
+	}
+}
+
+fn validate_r(root : &Cell, cell : &Cell, indices : &mut Vec<usize>, in_scope : &mut Vec<u32>, seen : &mut Vec<u32>, violations : &mut Vec<Violation>) {
+	let mut push = |kind : ViolationKind, indices : &Vec<usize>| {
+		let path = match CellPath::create(root.clone(), indices.clone()) {
+			Ok(x) => x,
+			Err(_) => panic!("validate_r: invalid indices - should not happen")
+		};
+		violations.push(Violation { path : path, kind : kind });
+	};
+
+	match cell {
+		&Cell::Simple { string : ref string_out } => {
+			let string = string_out.get_string();
+			if let Err(_) = SimpleString::create(string.clone()) {
+				push(ViolationKind::InvalidSimpleString { string : string }, indices);
+			}
+		},
+		&Cell::Complex { cells : ref cells_out } => {
+			if cells_out.len() < 2 {
+				push(ViolationKind::ShortComplex { len : cells_out.len() }, indices);
+			}
+		},
+		&Cell::Scope { id : id_out, .. } => {
+			if seen.contains(&id_out) {
+				push(ViolationKind::DuplicateScopeId { id : id_out }, indices);
+			}
+		},
+		&Cell::Var { id : id_out } => {
+			if !in_scope.contains(&id_out) {
+				push(ViolationKind::UnboundVar { id : id_out }, indices);
+			}
+		},
+		&Cell::Case {..} => {}
+	}
+
+	if let &Cell::Scope { id : id_out, .. } = cell {
+		seen.push(id_out);
+		in_scope.push(id_out);
+	}
+	for index in 0..cell.count_subcells() {
+		if let Some(child) = cell.get_subcell_ref(index) {
+			indices.push(index);
+			validate_r(root, child, indices, in_scope, seen, violations);
+			indices.pop();
+		}
+	}
+	if let &Cell::Scope {..} = cell {
+		in_scope.pop();
+	}
+}
+
+impl Cell {
+	// reports every violation, not only the first one
+	pub fn validate(&self) -> Result<(), Vec<Violation>> {
+		let mut violations = Vec::new();
+		validate_r(self, self, &mut vec![], &mut vec![], &mut vec![], &mut violations);
+		if violations.is_empty() {
+			Ok(())
+		} else {
+			Err(violations)
+		}
+	}
+
+	// like validate, but additionally requires self to be usable as a rule
+	pub fn validate_rule(&self) -> Result<(), Vec<Violation>> {
+		let mut violations = match self.validate() {
+			Ok(_) => Vec::new(),
+			Err(x) => x
+		};
+		let non_bool = match self {
+			&Cell::Simple {..} => (self.is_constant() && *self != true_cell() && *self != false_cell()) || *self == simple_by_str("="),
+			&Cell::Var {..} => true,
+			_ => false
+		};
+		if non_bool {
+			violations.insert(0, Violation { path : CellPath::create(self.clone(), vec![]).unwrap(), kind : ViolationKind::NonBoolRule });
+		}
+		if violations.is_empty() {
+			Ok(())
+		} else {
+			Err(violations)
+		}
+	}
+}
+
+impl Database {
+	// returns the violations together with the index of their rule
+	pub fn validate(&self) -> Result<(), Vec<(usize, Violation)>> {
+		let mut violations = Vec::new();
+		for (rule_id, rule) in self.get_rules().iter().enumerate() {
+			if let Err(x) = rule.validate_rule() {
+				for violation in x {
+					violations.push((rule_id, violation));
+				}
+			}
+		}
+		if violations.is_empty() {
+			Ok(())
+		} else {
+			Err(violations)
+		}
+	}
+}
+
+#[test]
+fn test_validate() {
+	assert!(scope(0, equals_cell(var(0), simple_by_str("a"))).validate().is_ok());
+
+	// ({0 (p 0 1)} 0 {0 x} (y))
+	let cell = Cell::Complex { cells : vec![
+		scope(0, complex(vec![simple_by_str("p"), var(0), var(1)])),
+		var(0),
+		scope(0, simple_by_str("x")),
+		Cell::Complex { cells : vec![simple_by_str("y")] }
+	]};
+	let violations = cell.validate().unwrap_err();
+	let found : Vec<(Vec<usize>, ViolationKind)> = violations.iter().map(|x| (x.get_path().get_indices(), x.get_kind())).collect();
+	assert_eq!(found, vec![
+		(vec![0, 0, 2], ViolationKind::UnboundVar { id : 1 }),
+		(vec![1], ViolationKind::UnboundVar { id : 0 }),
+		(vec![2], ViolationKind::DuplicateScopeId { id : 0 }),
+		(vec![3], ViolationKind::ShortComplex { len : 1 })
+	]);
+}
+
+#[test]
+fn test_validate_rule() {
+	assert!(simple_by_str("'true'").validate_rule().is_ok());
+	assert!(simple_by_str("p").validate_rule().is_ok());
+	assert_eq!(simple_by_str("'x'").validate_rule().unwrap_err()[0].get_kind(), ViolationKind::NonBoolRule);
+	assert_eq!(var(0).validate_rule().unwrap_err().len(), 2);
+
+	let db = Database::by_string("a. {0 (p 0)}.").unwrap();
+	assert!(db.validate().is_ok());
+}
@@ -0,0 +1,309 @@
+use std::collections::VecDeque;
+use std::ops::ControlFlow;
+use cell::{Cell, SimpleString};
+
+// what a visitor wants to happen after visiting a cell
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Visit {
+	Continue,
+	SkipChildren,
+	Stop
+}
+
+// every method defaults to Visit::Continue, so a visitor only implements the variants it cares about
+// path is the index path from the visited root to the current cell
+pub trait Visitor {
+	fn visit_simple(&mut self, _string : &SimpleString, _path : &[usize]) -> Visit { Visit::Continue }
+	fn visit_complex(&mut self, _cells : &[Cell], _path : &[usize]) -> Visit { Visit::Continue }
+	fn visit_scope(&mut self, _id : u32, _body : &Cell, _path : &[usize]) -> Visit { Visit::Continue }
+	fn visit_var(&mut self, _id : u32, _path : &[usize]) -> Visit { Visit::Continue }
+	fn visit_case(&mut self, _condition : &Cell, _conclusion : &Cell, _path : &[usize]) -> Visit { Visit::Continue }
+
+	// called after all subcells of cell were visited (not called for skipped or stopped cells)
+	fn leave(&mut self, _cell : &Cell, _path : &[usize]) {}
+
+	fn visit_cell(&mut self, cell : &Cell, path : &[usize]) -> Visit {
+		match cell {
+			&Cell::Simple { string : ref string_out } => self.visit_simple(string_out, path),
+			&Cell::Complex { cells : ref cells_out } => self.visit_complex(cells_out, path),
+			&Cell::Scope { id : id_out, body : ref body_out } => self.visit_scope(id_out, body_out, path),
+			&Cell::Var { id : id_out } => self.visit_var(id_out, path),
+			&Cell::Case { condition : ref cond_out, conclusion : ref conc_out } => self.visit_case(cond_out, conc_out, path)
+		}
+	}
+}
+
+// like Visitor, but threads an accumulator through the pre-order walk; ControlFlow::Break ends the walk.
+// every method defaults to passing acc on unchanged
+pub trait Fold<T> {
+	fn fold_simple(&mut self, acc : T, _string : &SimpleString, _path : &[usize]) -> ControlFlow<T, T> { ControlFlow::Continue(acc) }
+	fn fold_complex(&mut self, acc : T, _cells : &[Cell], _path : &[usize]) -> ControlFlow<T, T> { ControlFlow::Continue(acc) }
+	fn fold_scope(&mut self, acc : T, _id : u32, _body : &Cell, _path : &[usize]) -> ControlFlow<T, T> { ControlFlow::Continue(acc) }
+	fn fold_var(&mut self, acc : T, _id : u32, _path : &[usize]) -> ControlFlow<T, T> { ControlFlow::Continue(acc) }
+	fn fold_case(&mut self, acc : T, _condition : &Cell, _conclusion : &Cell, _path : &[usize]) -> ControlFlow<T, T> { ControlFlow::Continue(acc) }
+
+	fn fold_cell(&mut self, acc : T, cell : &Cell, path : &[usize]) -> ControlFlow<T, T> {
+		match cell {
+			&Cell::Simple { string : ref string_out } => self.fold_simple(acc, string_out, path),
+			&Cell::Complex { cells : ref cells_out } => self.fold_complex(acc, cells_out, path),
+			&Cell::Scope { id : id_out, body : ref body_out } => self.fold_scope(acc, id_out, body_out, path),
+			&Cell::Var { id : id_out } => self.fold_var(acc, id_out, path),
+			&Cell::Case { condition : ref cond_out, conclusion : ref conc_out } => self.fold_case(acc, cond_out, conc_out, path)
+		}
+	}
+}
+
+pub struct PreOrder<'a> {
+	stack : Vec<(&'a Cell, Vec<usize>)>,
+	last_depth : Option<usize> // stack length before the children of the cell returned last were pushed
+}
+
+pub struct PostOrder<'a> {
+	stack : Vec<(&'a Cell, Vec<usize>, bool)> // bool: children already pushed
+}
+
+pub struct BreadthFirst<'a> {
+	queue : VecDeque<(&'a Cell, Vec<usize>)>
+}
+
+fn push_children<'a>(stack : &mut Vec<(&'a Cell, Vec<usize>)>, cell : &'a Cell, path : &[usize]) {
+	for index in (0..cell.count_subcells()).rev() {
+		if let Some(child) = cell.get_subcell_ref(index) {
+			let mut child_path = path.to_vec();
+			child_path.push(index);
+			stack.push((child, child_path));
+		}
+	}
+}
+
+impl<'a> Iterator for PreOrder<'a> {
+	type Item = (&'a Cell, Vec<usize>);
+
+	fn next(&mut self) -> Option<(&'a Cell, Vec<usize>)> {
+		let (cell, path) = self.stack.pop()?;
+		self.last_depth = Some(self.stack.len());
+		push_children(&mut self.stack, cell, &path);
+		Some((cell, path))
+	}
+}
+
+impl<'a> PreOrder<'a> {
+	// do not descend into the subcells of the cell that was returned last
+	// -- does nothing, if next() has not been called yet or skip_children was called for that cell already
+	pub fn skip_children(&mut self) {
+		if let Some(depth) = self.last_depth.take() {
+			self.stack.truncate(depth);
+		}
+	}
+}
+
+impl<'a> Iterator for PostOrder<'a> {
+	type Item = (&'a Cell, Vec<usize>);
+
+	fn next(&mut self) -> Option<(&'a Cell, Vec<usize>)> {
+		loop {
+			let (cell, path, expanded) = self.stack.pop()?;
+			if expanded || cell.count_subcells() == 0 {
+				return Some((cell, path));
+			}
+			self.stack.push((cell, path.clone(), true));
+			for index in (0..cell.count_subcells()).rev() {
+				if let Some(child) = cell.get_subcell_ref(index) {
+					let mut child_path = path.clone();
+					child_path.push(index);
+					self.stack.push((child, child_path, false));
+				}
+			}
+		}
+	}
+}
+
+impl<'a> Iterator for BreadthFirst<'a> {
+	type Item = (&'a Cell, Vec<usize>);
+
+	fn next(&mut self) -> Option<(&'a Cell, Vec<usize>)> {
+		let (cell, path) = self.queue.pop_front()?;
+		for index in 0..cell.count_subcells() {
+			if let Some(child) = cell.get_subcell_ref(index) {
+				let mut child_path = path.clone();
+				child_path.push(index);
+				self.queue.push_back((child, child_path));
+			}
+		}
+		Some((cell, path))
+	}
+}
+
+impl Cell {
+	pub fn pre_order(&self) -> PreOrder<'_> {
+		PreOrder { stack : vec![(self, vec![])], last_depth : None }
+	}
+
+	pub fn post_order(&self) -> PostOrder<'_> {
+		PostOrder { stack : vec![(self, vec![], false)] }
+	}
+
+	pub fn breadth_first(&self) -> BreadthFirst<'_> {
+		BreadthFirst { queue : vec![(self, vec![])].into_iter().collect() }
+	}
+
+	// walks the cell in pre-order; returns false if the visitor stopped early
+	pub fn accept<V : Visitor>(&self, visitor : &mut V) -> bool {
+		let mut path = Vec::new();
+		self.accept_r(visitor, &mut path)
+	}
+
+	fn accept_r<V : Visitor>(&self, visitor : &mut V, path : &mut Vec<usize>) -> bool {
+		match visitor.visit_cell(self, path) {
+			Visit::Stop => return false,
+			Visit::SkipChildren => return true,
+			Visit::Continue => {}
+		}
+		for index in 0..self.count_subcells() {
+			if let Some(child) = self.get_subcell_ref(index) {
+				path.push(index);
+				let go_on = child.accept_r(visitor, path);
+				path.pop();
+				if !go_on {
+					return false;
+				}
+			}
+		}
+		visitor.leave(self, path);
+		true
+	}
+
+	// pre-order fold over all subcells (including self)
+	pub fn fold<T, F>(&self, init : T, mut f : F) -> T where F : FnMut(T, &Cell, &[usize]) -> T {
+		let mut t = init;
+		for (cell, path) in self.pre_order() {
+			t = f(t, cell, &path);
+		}
+		t
+	}
+
+	// pre-order fold with a Fold implementation, stops at the first ControlFlow::Break
+	pub fn fold_with<T, F : Fold<T>>(&self, init : T, folder : &mut F) -> T {
+		self.try_fold(init, |acc, cell, path| folder.fold_cell(acc, cell, path))
+	}
+
+	// like fold, but f may stop the traversal by returning ControlFlow::Break
+	pub fn try_fold<T, F>(&self, init : T, mut f : F) -> T where F : FnMut(T, &Cell, &[usize]) -> ControlFlow<T, T> {
+		let mut t = init;
+		for (cell, path) in self.pre_order() {
+			match f(t, cell, &path) {
+				ControlFlow::Continue(x) => t = x,
+				ControlFlow::Break(x) => return x
+			}
+		}
+		t
+	}
+}
+
+#[test]
+fn test_orders() {
+	use gen::*;
+
+	// (a {0 0} b)
+	let cell = complex(vec![simple_by_str("a"), scope(0, var(0)), simple_by_str("b")]);
+
+	let pre : Vec<Vec<usize>> = cell.pre_order().map(|(_, p)| p).collect();
+	assert_eq!(pre, vec![vec![], vec![0], vec![1], vec![1, 0], vec![2]]);
+
+	let post : Vec<Vec<usize>> = cell.post_order().map(|(_, p)| p).collect();
+	assert_eq!(post, vec![vec![0], vec![1, 0], vec![1], vec![2], vec![]]);
+
+	let bfs : Vec<Vec<usize>> = cell.breadth_first().map(|(_, p)| p).collect();
+	assert_eq!(bfs, vec![vec![], vec![0], vec![1], vec![2], vec![1, 0]]);
+
+	for (sub, path) in cell.pre_order() {
+		assert_eq!(*sub, ::navi::CellPath::create(cell.clone(), path).unwrap().get_cell());
+	}
+}
+
+#[test]
+fn test_skip_children() {
+	use gen::*;
+
+	let cell = complex(vec![scope(0, var(0)), simple_by_str("b")]);
+	let mut iter = cell.pre_order();
+	let mut paths = Vec::new();
+	while let Some(item) = iter.next() {
+		if let &Cell::Scope {..} = item.0 {
+			iter.skip_children();
+		}
+		paths.push(item.1);
+	}
+	assert_eq!(paths, vec![vec![], vec![0], vec![1]]);
+
+	// skipping after a leaf or twice does not drop the siblings
+	let cell = complex(vec![simple_by_str("a"), complex(vec![simple_by_str("b"), simple_by_str("c")]), simple_by_str("d")]);
+	let mut iter = cell.pre_order();
+	iter.skip_children();
+	let mut paths = Vec::new();
+	while let Some(item) = iter.next() {
+		if item.1 == vec![0] || item.1 == vec![1] {
+			iter.skip_children();
+			iter.skip_children();
+		}
+		paths.push(item.1);
+	}
+	assert_eq!(paths, vec![vec![], vec![0], vec![1], vec![2]]);
+}
+
+#[test]
+fn test_visitor() {
+	use gen::*;
+
+	struct VarCounter { count : usize, scopes : usize }
+	impl Visitor for VarCounter {
+		fn visit_var(&mut self, _id : u32, _path : &[usize]) -> Visit {
+			self.count += 1;
+			Visit::Continue
+		}
+		fn visit_scope(&mut self, _id : u32, _body : &Cell, _path : &[usize]) -> Visit {
+			self.scopes += 1;
+			if self.scopes == 2 { Visit::Stop } else { Visit::Continue }
+		}
+	}
+
+	let cell = complex(vec![scope(0, var(0)), var(1), scope(2, var(2)), var(3)]);
+	let mut counter = VarCounter { count : 0, scopes : 0 };
+	assert!(!cell.accept(&mut counter));
+	assert_eq!(counter.count, 2);
+}
+
+#[test]
+fn test_fold() {
+	use gen::*;
+
+	let cell = equals_cell(simple_by_str("a"), complex(vec![simple_by_str("a"), simple_by_str("b")]));
+	let name = "a".to_string();
+	let count = cell.fold(0, |n, c, _| if let &Cell::Simple { ref string } = c { if string.get_string() == name { n + 1 } else { n } } else { n });
+	assert_eq!(count, 2);
+
+	let first_b = cell.try_fold(None, |_, c, path| {
+		if *c == simple_by_str("b") { ControlFlow::Break(Some(path.to_vec())) } else { ControlFlow::Continue(None) }
+	});
+	assert_eq!(first_b, Some(vec![2, 1]));
+}
+
+#[test]
+fn test_fold_trait() {
+	use gen::*;
+
+	// collects the ids of the vars up to the first case cell
+	struct Vars;
+	impl Fold<Vec<u32>> for Vars {
+		fn fold_var(&mut self, mut acc : Vec<u32>, id : u32, _path : &[usize]) -> ControlFlow<Vec<u32>, Vec<u32>> {
+			acc.push(id);
+			ControlFlow::Continue(acc)
+		}
+		fn fold_case(&mut self, acc : Vec<u32>, _condition : &Cell, _conclusion : &Cell, _path : &[usize]) -> ControlFlow<Vec<u32>, Vec<u32>> {
+			ControlFlow::Break(acc)
+		}
+	}
+
+	let cell = complex(vec![scope(0, var(0)), var(1), case(var(2), var(3)), var(4)]);
+	assert_eq!(cell.fold_with(Vec::new(), &mut Vars), vec![0, 1]);
+}
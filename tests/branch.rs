@@ -0,0 +1,108 @@
+extern crate libsrl;
+use libsrl::db::Database;
+use libsrl::navi::{CellID, RuleHandle};
+
+#[test]
+fn test_fork() {
+	let db = Database::by_string("{0 wow}. = 'false' (= 'true' x).").unwrap();
+	let mut fork = db.fork();
+	assert!(fork.is_branch_of(&db));
+	assert!(fork.compare(&db).unwrap().is_empty());
+
+	fork.add_eqt(CellID::create(1, vec![0])).unwrap();
+	assert_eq!(db.count_rules(), 3);
+	assert_eq!(fork.count_rules(), 4);
+	assert!(db.get_proof_log().is_empty());
+
+	// source rules stay write protected in every branch
+	assert!(fork.delete_rule(1).is_err());
+	assert!(fork.fork().delete_rule(2).is_err());
+
+	let diff = db.compare(&fork).unwrap();
+	assert!(diff.get_only_left().is_empty());
+	assert_eq!(diff.get_only_right(), &[RuleHandle::create(3)]);
+
+	let other = Database::by_string("{0 wow}. = 'false' (= 'true' x).").unwrap();
+	assert!(!other.is_branch_of(&db));
+	assert!(db.compare(&other).is_err());
+}
+
+#[test]
+fn test_branch_handles() {
+	let db = Database::by_string("{0 wow}. = 'false' (= 'true' x).").unwrap();
+	let mut left = db.fork();
+	let mut right = db.fork();
+	left.add_eqt(CellID::create(1, vec![0])).unwrap();
+	right.scope_creation(CellID::create(2, vec![2]), vec![vec![2]]).unwrap();
+
+	// handles are never handed out twice, even in different branches
+	assert_eq!(left.get_rule_handle(3).unwrap(), RuleHandle::create(3));
+	assert_eq!(right.get_rule_handle(3).unwrap(), RuleHandle::create(4));
+
+	let diff = left.compare(&right).unwrap();
+	assert_eq!(diff.get_only_left(), &[RuleHandle::create(3)]);
+	assert_eq!(diff.get_only_right(), &[RuleHandle::create(4)]);
+}
+
+#[test]
+fn test_merge() {
+	let db = Database::by_string("{0 wow}. = 'false' (= 'true' x).").unwrap();
+	let mut left = db.fork();
+	let mut right = db.fork();
+	left.add_eqt(CellID::create(1, vec![0])).unwrap();
+	right.scope_creation(CellID::create(2, vec![2]), vec![vec![2]]).unwrap();
+	right.rm_eqt(CellID::create(3, vec![2, 0, 2])).unwrap();
+	left.add_eqt(CellID::create(2, vec![])).unwrap();
+	right.add_eqt(CellID::create(2, vec![])).unwrap(); // derived in both branches
+
+	assert_eq!(left.merge(&right).unwrap(), 2);
+	assert_eq!(left.count_rules(), 7);
+	assert_eq!(left.get_proof_log().len(), 4);
+	assert!(left.verify_proof_log().is_ok());
+
+	// the merged rules are ordered by handle
+	let rm_eqt_rule = right.get_rule_by_handle(RuleHandle::create(5)).unwrap();
+	assert_eq!(left.get_rule(5), rm_eqt_rule);
+
+	// the proof log is written with the new positions
+	let mut proof_log = Vec::new();
+	left.write_proof_log(&mut proof_log).unwrap();
+	let proof_log = String::from_utf8(proof_log).unwrap();
+	assert_eq!(proof_log.lines().filter(|x| x.contains("-> #4@4/")).count(), 1);
+	assert_eq!(proof_log.lines().filter(|x| x.contains("-> #")).count(), 4);
+	for step in left.get_proof_log() {
+		let result = step.get_result();
+		assert_eq!(left.get_rule_index(result.get_handle().unwrap()).unwrap(), result.get_rule_id());
+	}
+
+	// merging again does nothing
+	assert_eq!(left.merge(&right).unwrap(), 0);
+	assert_eq!(left.get_proof_log().len(), 4);
+
+	assert_eq!(right.merge(&left).unwrap(), 1);
+	assert_eq!(right.count_rules(), 7);
+	assert!(right.verify_proof_log().is_ok());
+
+	let other = Database::by_string("{0 wow}.").unwrap();
+	assert!(left.merge(&other).is_err());
+}
+
+#[test]
+fn test_fork_in_thread() {
+	fn assert_send<T : Send>() {}
+	assert_send::<Database>();
+
+	let mut db = Database::by_string("{0 wow}. = 'false' (= 'true' x).").unwrap();
+	let mut fork = db.fork();
+	let thread = ::std::thread::spawn(move || {
+		fork.add_eqt(CellID::create(1, vec![0])).unwrap();
+		fork
+	});
+	db.add_eqt(CellID::create(2, vec![])).unwrap();
+	let fork = thread.join().unwrap();
+
+	// the handle counter is shared across threads, so both derived rules got distinct handles
+	assert_ne!(db.get_rule_handle(3).unwrap(), fork.get_rule_handle(3).unwrap());
+	assert_eq!(db.merge(&fork).unwrap(), 1);
+	assert_eq!(db.count_rules(), 5);
+}
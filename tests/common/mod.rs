@@ -0,0 +1,50 @@
+// the differential tests of kernel.rs and preview.rs: every law application is previewed, applied and checked by the kernel
+#![allow(dead_code)]
+
+use libsrl::db::Database;
+use libsrl::db::proof::LawApplication;
+use libsrl::kernel::{Certificate, Step};
+use libsrl::navi::CellID;
+
+pub static SRC : &'static str = "= 'false' (= 'true' x). {0 (= 'true' (p 0))}. (= a b). (f a b). [=> (= a b) (f a)]. = 'false' {0 (= 'false' (p 0))}. (= 'ok' 'wow'). {0 {1 (= 0 1)}}. [=> c d]. [=> (= 'false' c) d]. = 'false' (= (g a) (g a)).";
+
+// preview and apply have to give the same result (or the same error), the kernel has to agree up to the error message
+pub fn compare(db : &Database, application : &LawApplication) {
+	let preview = application.preview(db);
+	let mut applied = db.clone();
+	let law_result = application.apply(&mut applied);
+	match (&preview, &law_result) {
+		(&Ok(ref x), &Ok(ref y)) => {
+			assert_eq!(x, y, "{}", application);
+			assert_eq!(applied.count_rules(), db.count_rules() + 1);
+		},
+		(&Err(ref e1), &Err(ref e2)) => assert_eq!(e1.to_string(), e2.to_string(), "{}", application),
+		(x, y) => panic!("{}: preview {:?}, apply {:?}", application, x, y)
+	}
+
+	let inputs = application.get_inputs().iter().map(|x| (x.get_rule_id(), x.get_indices())).collect();
+	let step = Step::create(application.get_law(), inputs, application.get_argument());
+	let kernel_result = Certificate::create(db.get_rules().to_vec(), vec![step]).check();
+	match (law_result, kernel_result) {
+		(Ok(x), Ok(y)) => assert_eq!(vec![x], y, "{}", application),
+		(Err(e1), Err((_, e2))) => {
+			let kind1 = e1.get_law_error().map(|x| x.get_kind());
+			let kind2 = e2.get_law_error().map(|x| x.get_kind());
+			assert_eq!(kind1, kind2, "{}: '{}' vs '{}'", application, e1, e2);
+		},
+		(Ok(x), Err((_, e))) => panic!("{}: law derived '{}', kernel failed with '{}'", application, x, e),
+		(Err(e), Ok(x)) => panic!("{}: kernel derived '{:?}', law failed with '{}'", application, x, e)
+	}
+}
+
+pub fn all_cell_ids(db : &Database) -> Vec<CellID> {
+	let mut ids = Vec::new();
+	for (i, rule) in db.get_rules().iter().enumerate() {
+		for (_, indices) in rule.pre_order() {
+			ids.push(CellID::create(i, indices));
+		}
+	}
+	ids.push(CellID::create(1, vec![9])); // invalid index
+	ids.push(CellID::create(99, vec![])); // invalid rule
+	ids
+}
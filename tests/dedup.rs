@@ -0,0 +1,75 @@
+extern crate libsrl;
+use libsrl::db::Database;
+use libsrl::db::proof::LawApplication;
+use libsrl::cell::Cell;
+use libsrl::navi::CellID;
+
+#[test]
+fn test_dedup_mode() {
+	let mut db = Database::by_string("{0 wow}. = 'false' (= 'true' x). {1 (= 'true' wow)}.").unwrap();
+	db.set_dedup(true);
+	assert!(db.is_dedup());
+
+	// alpha-equivalent to the source rule 3
+	let rule = db.add_eqt(CellID::create(1, vec![0])).unwrap();
+	assert_eq!(db.count_rules(), 4);
+	assert_eq!(db.find_rule(&rule), Some(3));
+	assert!(db.get_proof_log().is_empty());
+
+	let application : LawApplication = "add_eqt #2/2".parse().unwrap();
+	assert_eq!(db.derive(&application).unwrap(), 4);
+	assert_eq!(db.derive(&application).unwrap(), 4);
+	assert_eq!(db.count_rules(), 5);
+	assert_eq!(db.get_proof_log().len(), 1);
+	assert_eq!(db.find_rule(&Cell::by_string("= 'false' (= 'true' (= 'true' x))").unwrap()), Some(4));
+
+	// once the rule is deleted, it can be derived again
+	db.delete_rule(4).unwrap();
+	assert_eq!(db.find_rule(&db.get_rule(2)), Some(2));
+	assert_eq!(db.derive(&application).unwrap(), 4);
+
+	db.set_dedup(false);
+	assert_eq!(db.derive(&application).unwrap(), 5);
+}
+
+#[test]
+fn test_dedup() {
+	let mut db = Database::by_string("{0 wow}. = 'false' (= 'true' x).").unwrap();
+	db.add_eqt(CellID::create(1, vec![0])).unwrap();
+	db.add_eqt(CellID::create(1, vec![0])).unwrap();
+	db.add_eqt(CellID::create(4, vec![0])).unwrap();
+	db.rm_eqt(CellID::create(4, vec![0, 2])).unwrap(); // {0 wow} again
+	assert_eq!(db.count_rules(), 7);
+	let handle3 = db.get_rule_handle(3).unwrap();
+
+	assert_eq!(db.dedup().unwrap(), 2);
+	assert_eq!(db.count_rules(), 5);
+	assert_eq!(db.get_rule(4).to_rule_string(), "{0 (= 'true' (= 'true' wow))}.");
+	assert_eq!(db.get_proof_log().len(), 2);
+	assert_eq!(db.get_proof_step(4).unwrap().get_inputs()[0].get_handle(), Some(handle3));
+	assert!(db.verify_proof_log().is_ok());
+
+	assert_eq!(db.dedup().unwrap(), 0);
+}
+
+#[test]
+fn test_dedup_positions() {
+	let mut db = Database::by_string("{0 wow}. = 'false' (= 'true' x).").unwrap();
+	db.add_eqt(CellID::create(1, vec![0])).unwrap();
+	db.add_eqt(CellID::create(1, vec![0])).unwrap();
+	db.add_eqt(CellID::create(2, vec![])).unwrap();
+	db.add_eqt(CellID::create(5, vec![])).unwrap();
+
+	// rule 4 is removed, so the later rules move one position up
+	assert_eq!(db.dedup().unwrap(), 1);
+	assert_eq!(db.count_rules(), 6);
+	for step in db.get_proof_log() {
+		let result = step.get_result();
+		assert_eq!(db.get_rule_index(result.get_handle().unwrap()).unwrap(), result.get_rule_id());
+		for input in step.get_inputs() {
+			assert_eq!(db.get_rule_index(input.get_handle().unwrap()).unwrap(), input.get_rule_id());
+		}
+	}
+	assert_eq!(db.get_proof_log()[2].get_inputs()[0].get_rule_id(), 4);
+	assert!(db.verify_proof_log().is_ok());
+}
@@ -0,0 +1,40 @@
+extern crate libsrl;
+use libsrl::db::Database;
+use libsrl::db::dtree::{is_candidate, is_indexed};
+use libsrl::cell::Cell;
+use libsrl::navi::CellID;
+
+fn linear_scan(db : &Database, term : &Cell) -> Vec<CellID> {
+	let mut result = Vec::new();
+	for (rule_id, rule) in db.get_rules().iter().enumerate() {
+		for (cell, indices) in rule.pre_order() {
+			if is_indexed(cell) && is_candidate(term, cell) {
+				result.push(CellID::create(rule_id, indices));
+			}
+		}
+	}
+	result
+}
+
+#[test]
+fn test_find_candidates() {
+	let mut db = Database::by_string("(= (f a) b). {0 (= (f 0) (g 0))}. (p (f b) (q a)). = 'true' (f c).").unwrap();
+
+	let terms = ["f a", "f c", "= (f a) _", "p (f b) (q 0)", "g x", "{0 0}", "{0 (p 0 0 0)}"];
+	for term in terms.iter() {
+		let term = Cell::by_string(term).unwrap();
+		assert_eq!(db.find_candidates(&term), linear_scan(&db, &term));
+	}
+
+	let term = Cell::by_string("f a").unwrap();
+	let ids : Vec<String> = db.find_candidates(&term).iter().map(|x| x.to_string()).collect();
+	assert_eq!(ids, vec!["#1/1", "#2/0.1"]);
+
+	db.add_eqt(CellID::create(1, vec![])).unwrap();
+	assert_eq!(db.find_candidates(&term), linear_scan(&db, &term));
+	assert_eq!(db.find_candidates(&term).last(), Some(&CellID::create(5, vec![2, 1])));
+
+	db.delete_rule(5).unwrap();
+	assert_eq!(db.find_candidates(&term), linear_scan(&db, &term));
+	assert_eq!(db.find_candidates(&term).len(), 2);
+}
@@ -0,0 +1,36 @@
+extern crate libsrl;
+use libsrl::db::Database;
+use libsrl::db::reason::Law;
+use libsrl::navi::CellID;
+use libsrl::error::{SRLError, LawErrorKind, NavigationErrorKind};
+
+#[test]
+fn test_law_error() {
+	let mut db = match Database::by_string("= 'false' {0 (= 'true' (p 0))}.") {
+		Ok(x) => x,
+		Err(_) => panic!("panic!")
+	};
+
+	let err = match db.scope_insertion(CellID::create(1, vec![2]), db.get_rule(0)) {
+		Ok(_) => panic!("should fail: wrapper is negative"),
+		Err(x) => x
+	};
+	assert_eq!(err.to_string(), "scope_insertion: wrapper is not positive in = 'false' <{0 (= 'true' (p 0))}>");
+	let law_error = err.get_law_error().unwrap();
+	assert_eq!(law_error.get_law(), Law::ScopeInsertion);
+	assert_eq!(law_error.get_kind(), LawErrorKind::WrapperNotPositive);
+	assert_eq!(law_error.get_path().unwrap().get_indices(), vec![2]);
+}
+
+#[test]
+fn test_navigation_error() {
+	let mut db = Database::by_string("a.").unwrap();
+	match db.add_eqt(CellID::create(1, vec![0])) {
+		Err(SRLError::Navigation { kind : NavigationErrorKind::InvalidIndex, .. }) => {},
+		x => panic!("unexpected: {:?}", x)
+	}
+	match db.add_eqt(CellID::create(5, vec![])) {
+		Err(ref err @ SRLError::Navigation { .. }) => assert_eq!(err.to_string(), "CellID::to_path: index of rule_id out of range"),
+		x => panic!("unexpected: {:?}", x)
+	}
+}
@@ -0,0 +1,49 @@
+extern crate libsrl;
+use libsrl::db::Database;
+use libsrl::navi::{CellID, CellPath};
+use libsrl::cell::Cell;
+use libsrl::gen::*;
+
+#[test]
+fn test_try_counterparts() {
+	let db = Database::by_string("p a.").unwrap();
+	assert!(db.try_get_rule(1).is_ok());
+	assert!(db.try_get_rule(2).is_err());
+
+	let cell = db.get_rule(1);
+	assert_eq!(cell.try_get_subcell(1).unwrap(), simple_by_str("a"));
+	assert!(cell.try_get_subcell(2).is_err());
+	assert!(simple_by_str("a").try_get_subcell(0).is_err());
+	assert_eq!(cell.try_with_subcell(simple_by_str("b"), 1).unwrap().to_string(), "(p b)");
+	assert!(cell.try_with_subcell(simple_by_str("b"), 2).is_err());
+
+	assert!(try_complex(vec![simple_by_str("a")]).is_err());
+	assert!(try_simple_by_str("a'b").is_err());
+
+	let path = CellPath::create(cell, vec![0]).unwrap();
+	assert!(path.try_replace_by(Cell::Complex { cells : vec![] }).is_err());
+
+	assert!(Database::by_string("a. b").is_err());
+	assert!(Database::by_string(".a.").is_err());
+}
+
+#[test]
+fn test_laws_do_not_panic() {
+	let mut db = Database::by_string("= 'true' y. = 'false' (= 'true' x).").unwrap();
+	let short = Cell::Complex { cells : vec![simple_by_str("x")] };
+
+	assert!(db.case_creation(CellID::create(1, vec![]), short.clone()).is_err());
+	assert!(db.case_creation(CellID::create(1, vec![]), Cell::Complex { cells : vec![] }).is_err());
+	assert!(db.scope_insertion(CellID::create(0, vec![]), short).is_err());
+	assert!(db.scope_creation(CellID::create(2, vec![2]), vec![vec![0, 1], vec![0, 1, 0]]).is_err());
+	assert!(db.rm_eqt(CellID::create(9, vec![])).is_err());
+	assert!(db.equals_law_impl(CellID::create(1, vec![5]), CellID::create(1, vec![])).is_err());
+	assert!(db.declaration(CellID::create(1, vec![]), "'wrong").is_err());
+
+	// empty indices create a scope without vars
+	match db.scope_creation(CellID::create(2, vec![2]), vec![]) {
+		Ok(x) => assert_eq!(x.to_rule_string(), "= 'false' {0 (= 'true' x)}."),
+		Err(srl_error) => panic!("panic! err: {:?}", srl_error)
+	}
+	assert_eq!(db.count_rules(), 4);
+}
@@ -0,0 +1,110 @@
+extern crate libsrl;
+use libsrl::db::Database;
+use libsrl::db::goal::Event;
+use libsrl::navi::CellID;
+use libsrl::cell::Cell;
+
+fn cell(string : &str) -> Cell {
+	Cell::by_string(string).unwrap()
+}
+
+#[test]
+fn test_goal_proved() {
+	let mut db = Database::by_string("{0 wow}. = 'false' (= 'true' x). (= a b). (f a).").unwrap();
+	assert_eq!(db.add_goal(cell("= 'true' (f b)")).unwrap(), 0);
+	assert_eq!(db.add_goal(cell("{5 (= 'true' wow)}")).unwrap(), 1); // alpha-equivalent to the rule derived below
+	assert_eq!(db.get_open_goals(), vec![0, 1]);
+
+	db.add_eqt(CellID::create(1, vec![0])).unwrap(); // 5
+	let handle = db.get_rule_handle(5).unwrap();
+	assert_eq!(db.get_open_goals(), vec![0]);
+	assert_eq!(db.get_goals()[1].get_proof(), Some(handle));
+	assert_eq!(db.take_events(), vec![Event::GoalProved { goal : 1, rule : handle }]);
+	assert!(db.take_events().is_empty());
+
+	db.equals_law(CellID::create(4, vec![1]), CellID::create(3, vec![])).unwrap(); // 6
+	db.add_eqt(CellID::create(6, vec![])).unwrap(); // 7
+	assert!(db.get_open_goals().is_empty());
+	let steps = db.get_goal_steps(0).unwrap();
+	assert_eq!(steps.len(), 2);
+	assert_eq!(steps[0].get_result().get_handle(), db.get_rule_handle(6).ok());
+	assert_eq!(steps[1].get_result().get_handle(), db.get_rule_handle(7).ok());
+	assert!(db.get_goal_steps(2).is_err());
+
+	// a goal, that already is a rule, is proved at once (without proof steps)
+	assert_eq!(db.add_goal(cell("(= a b)")).unwrap(), 2);
+	assert!(db.get_goals()[2].is_proved());
+	assert!(db.get_goal_steps(2).unwrap().is_empty());
+}
+
+#[test]
+fn test_undo_reopens_goal() {
+	let mut db = Database::by_string("{0 wow}.").unwrap();
+	db.add_goal(cell("{0 (= 'true' wow)}")).unwrap();
+	db.add_eqt(CellID::create(1, vec![0])).unwrap();
+	assert!(db.get_open_goals().is_empty());
+	db.undo().unwrap();
+	assert_eq!(db.get_open_goals(), vec![0]);
+	db.redo().unwrap();
+	assert!(db.get_open_goals().is_empty());
+	let handle = db.get_rule_handle(2).unwrap();
+	assert_eq!(db.take_events(), vec![
+		Event::GoalProved { goal : 0, rule : handle },
+		Event::GoalReopened { goal : 0 },
+		Event::GoalProved { goal : 0, rule : handle }
+	]);
+}
+
+#[test]
+fn test_rollback_events() {
+	let mut db = Database::by_string("{0 wow}.").unwrap();
+	db.add_goal(cell("{0 (= 'true' wow)}")).unwrap();
+	db.add_eqt(CellID::create(1, vec![0])).unwrap();
+	let proved = Event::GoalProved { goal : 0, rule : db.get_rule_handle(2).unwrap() };
+
+	// events pending at begin_transaction and taken inside it are not delivered again
+	db.begin_transaction();
+	assert_eq!(db.take_events(), vec![proved.clone()]);
+	db.rollback().unwrap();
+	assert!(db.take_events().is_empty());
+
+	// a goal proved inside a rolled back transaction is reported as reopened
+	db.undo().unwrap();
+	db.take_events();
+	db.begin_transaction();
+	db.add_eqt(CellID::create(1, vec![0])).unwrap();
+	let proved = Event::GoalProved { goal : 0, rule : db.get_rule_handle(2).unwrap() };
+	assert_eq!(db.take_events(), vec![proved]);
+	db.rollback().unwrap();
+	assert_eq!(db.get_open_goals(), vec![0]);
+	assert_eq!(db.take_events(), vec![Event::GoalReopened { goal : 0 }]);
+}
+
+#[test]
+fn test_inconsistency() {
+	let mut db = Database::by_string("(= a 'false'). a. (= b 'true'). (= b 'false').").unwrap();
+	assert!(!db.is_inconsistent());
+	db.equals_law(CellID::create(2, vec![]), CellID::create(1, vec![])).unwrap();
+	db.equals_law(CellID::create(4, vec![1]), CellID::create(3, vec![])).unwrap();
+	assert!(db.is_inconsistent());
+	let events = db.take_events();
+	assert_eq!(events, vec![
+		Event::Inconsistency { rule : db.get_rule_handle(5).unwrap() },
+		Event::Inconsistency { rule : db.get_rule_handle(6).unwrap() }
+	]);
+}
+
+#[test]
+fn test_goal_section() {
+	let db = Database::by_string("{0 wow}.\ngoal:\n{1 (= 'true' wow)}.\nderived:\n{0 (= 'true' wow)}.\n").unwrap();
+	assert_eq!(db.count_rules(), 3);
+	assert!(db.get_goals()[0].is_proved());
+
+	let db = Database::from_reader("a.\ngoal:\n= 'true' a.\n".as_bytes()).unwrap();
+	assert_eq!(db.get_open_goals(), vec![0]);
+	let mut buffer : Vec<u8> = Vec::new();
+	db.write_to(&mut buffer).unwrap();
+	assert_eq!(String::from_utf8(buffer).unwrap(), "a.\nderived:\ngoal:\n= 'true' a.\n");
+
+	assert!(Database::by_string("a.\ngoal:\nb.\ngoal:\nc.").is_err());
+}
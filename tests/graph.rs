@@ -0,0 +1,55 @@
+extern crate libsrl;
+use libsrl::db::Database;
+use libsrl::navi::{CellID, RuleHandle};
+
+fn handles(ids : &[u64]) -> Vec<RuleHandle> {
+	ids.iter().map(|x| RuleHandle::create(*x)).collect()
+}
+
+#[test]
+fn test_dependencies() {
+	let mut db = Database::by_string("{0 wow}. = 'false' (= 'true' x). (= a b). (f a).").unwrap();
+	db.add_eqt(CellID::create(1, vec![0])).unwrap(); // 5 <- 1
+	db.equals_law(CellID::create(4, vec![1]), CellID::create(3, vec![])).unwrap(); // 6 <- 4, 3
+	db.add_eqt(CellID::create(6, vec![])).unwrap(); // 7 <- 6
+	db.rm_eqt(CellID::create(5, vec![0, 2])).unwrap(); // 8 <- 5
+	db.delete_rule(6).unwrap();
+
+	let graph = db.get_dependency_graph();
+	assert!(graph.contains(RuleHandle::create(6)));
+	assert_eq!(graph.get_index(RuleHandle::create(6)), None);
+	assert_eq!(graph.get_index(RuleHandle::create(7)), Some(6));
+	assert!(graph.is_axiom(RuleHandle::create(3)));
+	assert!(!graph.is_axiom(RuleHandle::create(7)));
+
+	assert_eq!(graph.get_inputs(RuleHandle::create(6)), handles(&[3, 4]));
+	assert_eq!(graph.get_dependencies(RuleHandle::create(7)), handles(&[3, 4, 6]));
+	assert_eq!(graph.get_axioms(RuleHandle::create(7)), handles(&[3, 4]));
+	assert_eq!(graph.get_dependents(RuleHandle::create(1)), handles(&[5, 8]));
+	assert!(graph.get_dependents(RuleHandle::create(2)).is_empty());
+
+	// by index, deleted rules are skipped
+	assert_eq!(db.get_rule_dependencies(6).unwrap(), vec![3, 4]);
+	assert_eq!(db.get_rule_dependents(3).unwrap(), vec![6]);
+	assert_eq!(db.get_rule_dependents(1).unwrap(), vec![5, 7]);
+	assert!(db.get_rule_dependencies(9).is_err());
+}
+
+#[test]
+fn test_export() {
+	let mut db = Database::by_string("{0 wow}.").unwrap();
+	db.add_eqt(CellID::create(1, vec![0])).unwrap();
+	let graph = db.get_dependency_graph();
+
+	let dot = graph.to_dot();
+	assert!(dot.starts_with("digraph dependencies {\n"));
+	assert!(dot.contains("\tr1 [label=\"#1@1 {0 wow}.\", shape=box];\n"));
+	assert!(dot.contains("\tr2 [label=\"#2@2 {0 (= 'true' wow)}.\"];\n"));
+	assert!(dot.contains("\tr2 -> r1 [label=\"add_eqt\"];\n"));
+	assert!(dot.ends_with("}\n"));
+
+	let json = graph.to_json();
+	assert!(json.starts_with("{\"nodes\":[{\"handle\":0,\"index\":0,"));
+	assert!(json.ends_with(",{\"handle\":2,\"index\":2,\"rule\":\"{0 (= 'true' wow)}.\",\"law\":\"add_eqt\",\"inputs\":[1]}]}"));
+	assert!(json.contains("{\"handle\":1,\"index\":1,\"rule\":\"{0 wow}.\",\"law\":null,\"inputs\":[]}"));
+}
@@ -0,0 +1,101 @@
+extern crate libsrl;
+use libsrl::db::Database;
+use libsrl::db::reason::Law;
+use libsrl::error::SRLError;
+use libsrl::navi::CellID;
+
+#[test]
+fn test_undo_redo() {
+	let mut db = Database::by_string("{0 wow}. = 'false' {0 (= 'false' (p 0))}.").unwrap();
+	let original = db.clone();
+	assert!(!db.can_undo());
+	assert!(db.undo().is_none());
+
+	db.add_eqt(CellID::create(1, vec![0])).unwrap();
+	db.declaration(CellID::create(2, vec![]), "q").unwrap();
+	let derived = db.clone();
+
+	assert_eq!(db.undo().unwrap().get_law(), Law::Declaration);
+	assert_eq!(db.undo().unwrap().get_law(), Law::AddEqt);
+	assert!(db == original);
+	assert!(db.get_proof_log().is_empty());
+	assert!(!db.contains_cellname("q"));
+
+	assert_eq!(db.redo().unwrap().get_law(), Law::AddEqt);
+	assert_eq!(db.redo().unwrap().get_law(), Law::Declaration);
+	assert!(db.redo().is_none());
+	assert!(db == derived);
+	assert_eq!(db.get_handles(), derived.get_handles());
+	assert_eq!(db.get_proof_log(), derived.get_proof_log());
+	assert!(db.contains_cellname("q"));
+
+	// a new law application clears the redo steps
+	db.undo().unwrap();
+	assert!(db.can_redo());
+	db.add_eqt(CellID::create(3, vec![])).unwrap();
+	assert!(!db.can_redo());
+
+	// undoing an application, whose rule has been deleted, only removes it from the proof log
+	db.delete_rule(4).unwrap();
+	db.undo().unwrap();
+	assert_eq!(db.count_rules(), 4);
+	assert_eq!(db.get_proof_log().len(), 1);
+}
+
+#[test]
+fn test_delete_rule_clears_redo() {
+	let mut db = Database::by_string("{0 wow}. = 'false' (= 'true' x).").unwrap();
+	db.add_eqt(CellID::create(1, vec![0])).unwrap();
+	db.add_eqt(CellID::create(3, vec![0])).unwrap();
+	db.undo().unwrap();
+	assert!(db.can_redo());
+
+	// the undone step used rule 3, redoing it after the deletion would log a step with a deleted input
+	db.delete_rule(3).unwrap();
+	assert!(!db.can_redo());
+	assert!(db.redo().is_none());
+	assert!(db.verify_proof_log().is_ok());
+}
+
+#[test]
+fn test_transaction() {
+	let mut db = Database::by_string("{0 wow}. = 'false' (= 'true' x).").unwrap();
+	let original = db.clone();
+
+	assert!(db.commit().is_err());
+	assert!(db.rollback().is_err());
+
+	db.begin_transaction();
+	db.add_eqt(CellID::create(1, vec![0])).unwrap();
+	db.begin_transaction();
+	db.add_eqt(CellID::create(3, vec![])).unwrap();
+	assert_eq!(db.count_transactions(), 2);
+	db.rollback().unwrap();
+	assert_eq!(db.count_rules(), 4);
+	db.commit().unwrap();
+	assert_eq!(db.count_rules(), 4);
+	assert_eq!(db.count_transactions(), 0);
+
+	db.begin_transaction();
+	db.delete_rule(3).unwrap();
+	db.scope_creation(CellID::create(2, vec![2]), vec![vec![2]]).unwrap();
+	db.rollback().unwrap();
+	assert_eq!(db.count_rules(), 4);
+	assert_eq!(db.get_proof_log().len(), 1);
+
+	let result = db.transaction(|db| {
+		db.add_eqt(CellID::create(3, vec![]))?;
+		db.rm_eqt(CellID::create(1, vec![0]))?; // fails, so the whole transaction is rolled back
+		Ok(())
+	});
+	assert!(result.is_err());
+	assert_eq!(db.count_rules(), 4);
+
+	let rule = db.transaction(|db| -> Result<_, SRLError> {
+		db.add_eqt(CellID::create(3, vec![]))
+	}).unwrap();
+	assert_eq!(db.get_rule(4), rule);
+
+	while db.undo().is_some() {}
+	assert!(db == original);
+}
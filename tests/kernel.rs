@@ -0,0 +1,135 @@
+extern crate libsrl;
+use libsrl::db::Database;
+use libsrl::db::proof::{LawApplication, LawArgument};
+use libsrl::db::reason::Law;
+use libsrl::kernel::{Certificate, Step};
+use libsrl::navi::CellID;
+use libsrl::cell::Cell;
+use libsrl::error::LawErrorKind;
+
+mod common;
+use common::{SRC, compare, all_cell_ids};
+
+#[test]
+fn test_kernel_single_input_laws() {
+	let db = Database::by_string(SRC).unwrap();
+	let ids = all_cell_ids(&db);
+	let cells = ["a", "(f b c)", "{0 (= 0 a)}", "[=> a b]"];
+
+	for id in &ids {
+		for law in [Law::InequalConstants, Law::AddEqt, Law::RmEqt, Law::ScopeExchange].iter() {
+			compare(&db, &LawApplication::create(*law, vec![id.clone()], None));
+		}
+		for cell in cells.iter() {
+			let argument = Some(LawArgument::Cell(Cell::by_string(cell).unwrap()));
+			compare(&db, &LawApplication::create(Law::ScopeInsertion, vec![id.clone()], argument.clone()));
+			compare(&db, &LawApplication::create(Law::CaseCreation, vec![id.clone()], argument));
+		}
+		for name in ["q", "a"].iter() {
+			compare(&db, &LawApplication::create(Law::Declaration, vec![id.clone()], Some(LawArgument::Name(name.to_string()))));
+		}
+		for paths in [vec![], vec![vec![1]], vec![vec![2], vec![1]], vec![vec![1, 1], vec![2, 1]], vec![vec![0, 5]]].iter() {
+			compare(&db, &LawApplication::create(Law::ScopeCreation, vec![id.clone()], Some(LawArgument::Paths(paths.clone()))));
+		}
+	}
+}
+
+#[test]
+fn test_kernel_two_input_laws() {
+	let db = Database::by_string(SRC).unwrap();
+	let ids = all_cell_ids(&db);
+
+	for id1 in &ids {
+		for id2 in &ids {
+			for law in [Law::EqualsLaw, Law::EqualsLawImpl, Law::ImplicationsDerivation].iter() {
+				compare(&db, &LawApplication::create(*law, vec![id1.clone(), id2.clone()], None));
+			}
+		}
+	}
+}
+
+#[test]
+fn test_kernel_wrong_arguments() {
+	let certificate = Certificate::create(vec![Cell::by_string("a").unwrap()], vec![Step::create(Law::AddEqt, vec![], None)]);
+	assert_eq!(certificate.check().unwrap_err().0, 0);
+	let certificate = Certificate::create(vec![Cell::by_string("a").unwrap()], vec![Step::create(Law::Declaration, vec![(0, vec![])], None)]);
+	let srl_error = certificate.check().unwrap_err().1;
+	assert_eq!(srl_error.get_law_error().unwrap().get_kind(), LawErrorKind::WrongArguments);
+
+	// the database reports the same
+	let db = Database::by_string("= 'false' {0 (= 'false' (p 0))}.").unwrap();
+	let srl_error = db.preview(&LawApplication::create(Law::Declaration, vec![CellID::create(1, vec![])], None)).unwrap_err();
+	assert_eq!(srl_error.get_law_error().unwrap().get_kind(), LawErrorKind::WrongArguments);
+	let srl_error = db.preview(&LawApplication::create(Law::AddEqt, vec![], None)).unwrap_err();
+	assert_eq!(srl_error.get_law_error().unwrap().get_kind(), LawErrorKind::WrongArguments);
+}
+
+#[test]
+fn test_verify_proof_log() {
+	let mut db = Database::by_string("{0 wow}. = 'false' {0 (= 'false' (p 0))}. = 'false' (= 'true' x).").unwrap();
+	db.add_eqt(CellID::create(1, vec![0])).unwrap();
+	db.declaration(CellID::create(2, vec![]), "q").unwrap();
+	db.scope_creation(CellID::create(3, vec![2]), vec![vec![2]]).unwrap();
+	db.delete_rule(4).unwrap();
+	db.rm_eqt(CellID::create(5, vec![2, 0, 2])).unwrap();
+	assert!(db.verify_proof_log().is_ok());
+
+	let certificate = db.get_certificate().unwrap();
+	assert_eq!(certificate.get_axioms().len(), 4);
+	assert_eq!(certificate.get_steps().len(), 4);
+	let derived = certificate.check().unwrap();
+	assert_eq!(derived.last(), db.get_rules().last());
+
+	// a step using a wrong cell fails at this step
+	let mut steps = certificate.get_steps().to_vec();
+	steps[3] = Step::create(Law::RmEqt, vec![(6, vec![2, 0])], None);
+	let (i, srl_error) = Certificate::create(certificate.get_axioms().to_vec(), steps).check().unwrap_err();
+	assert_eq!(i, 3);
+	assert!(srl_error.get_law_error().is_some());
+
+	// a step may not use its own or later results
+	let mut steps = certificate.get_steps().to_vec();
+	steps[0] = Step::create(Law::AddEqt, vec![(5, vec![])], None);
+	assert_eq!(Certificate::create(certificate.get_axioms().to_vec(), steps).check().unwrap_err().0, 0);
+}
+
+#[test]
+fn test_declaration_after_delete() {
+
+	let mut db = Database::by_string("= 'false' {0 (= 'false' (p 0))}. = 'false' {0 (= 'false' (r 0))}.").unwrap();
+	db.declaration(CellID::create(1, vec![]), "q").unwrap();
+	db.add_eqt(CellID::create(3, vec![])).unwrap();
+	let srl_error = db.declaration(CellID::create(2, vec![]), "q").unwrap_err();
+	assert_eq!(srl_error.get_law_error().unwrap().get_kind(), LawErrorKind::NameOccurs);
+
+	// once the rules using q are deleted, q can be declared again
+	db.delete_rule(4).unwrap();
+	db.delete_rule(3).unwrap();
+	db.declaration(CellID::create(2, vec![]), "q").unwrap();
+	assert!(db.verify_proof_log().is_ok());
+
+	// the kernel forgets the deleted rules after their last use
+	let certificate = db.get_certificate().unwrap();
+	assert!(certificate.get_steps()[1].get_forgotten().is_empty());
+	assert_eq!(certificate.get_steps()[2].get_forgotten(), &[3, 4]);
+
+	// and a forgotten theorem can not be used anymore
+	let mut steps = certificate.get_steps().to_vec();
+	steps[2] = Step::create(Law::AddEqt, vec![(3, vec![])], None).with_forgotten(vec![3, 4]);
+	assert_eq!(Certificate::create(certificate.get_axioms().to_vec(), steps).check().unwrap_err().0, 2);
+}
+
+#[test]
+fn test_malformed_certificate() {
+	let axioms = vec![Cell::by_string("= 'false' (= 'true' x)").unwrap(), Cell::by_string("= 'true' a").unwrap()];
+	let steps = vec![
+		Step::create(Law::EqualsLaw, vec![(0, vec![2, 2, 7]), (0, vec![2])], None),
+		Step::create(Law::RmEqt, vec![(1, vec![5])], None),
+		Step::create(Law::ScopeCreation, vec![(0, vec![2])], Some(LawArgument::Paths(vec![vec![9], vec![1, 1]]))),
+		Step::create(Law::AddEqt, vec![(7, vec![])], None),
+		Step::create(Law::AddEqt, vec![], None)
+	];
+	for step in steps {
+		assert!(Certificate::create(axioms.clone(), vec![step]).check().is_err());
+	}
+}
@@ -0,0 +1,30 @@
+extern crate libsrl;
+use libsrl::db::Database;
+use libsrl::navi::CellID;
+
+mod common;
+use common::{SRC, compare, all_cell_ids};
+
+#[test]
+fn test_preview_agrees_with_apply() {
+	let db = Database::by_string(SRC).unwrap();
+	for id in &all_cell_ids(&db) {
+		for application in &[format!("add_eqt {}", id), format!("rm_eqt {}", id), format!("inequal_constants {}", id),
+				format!("scope_exchange {}", id), format!("case_creation {} c", id), format!("declaration {} q", id),
+				format!("equals_law {} #3", id), format!("scope_creation {} /", id)] {
+			compare(&db, &application.parse().unwrap());
+		}
+	}
+	assert_eq!(db.count_rules(), Database::by_string(SRC).unwrap().count_rules());
+}
+
+#[test]
+fn test_check_laws() {
+	let db = Database::by_string("{0 wow}. (= a b). (f a).").unwrap();
+	assert_eq!(db.check_add_eqt(CellID::create(1, vec![0])).unwrap().to_rule_string(), "{0 (= 'true' wow)}.");
+	assert_eq!(db.check_equals_law(CellID::create(3, vec![1]), CellID::create(2, vec![])).unwrap().to_rule_string(), "f b.");
+	assert!(db.check_rm_eqt(CellID::create(1, vec![0])).unwrap_err().get_law_error().is_some());
+	assert!(db.check_add_eqt(CellID::create(9, vec![])).is_err());
+	assert_eq!(db.count_rules(), 4);
+	assert!(db.get_proof_log().is_empty());
+}
@@ -0,0 +1,53 @@
+extern crate libsrl;
+use libsrl::db::Database;
+use libsrl::db::proof::{parse_proof_log, LawArgument};
+use libsrl::db::reason::Law;
+use libsrl::navi::CellID;
+use libsrl::cell::Cell;
+
+#[test]
+fn test_proof_log() {
+	let mut db = Database::by_string("{0 wow}. = 'false' {0 (= 'false' (p 0))}.").unwrap();
+	db.add_eqt(CellID::create(1, vec![0])).unwrap();
+	db.declaration("2", "q").unwrap();
+	assert!(db.rm_eqt(CellID::create(1, vec![0])).is_err()); // failed applications are not logged
+
+	assert_eq!(db.get_proof_log().len(), 2);
+	assert!(db.get_proof_step(1).is_none());
+
+	let step = db.get_proof_step(4).unwrap();
+	assert_eq!(step.get_law(), Law::Declaration);
+	assert_eq!(step.get_inputs(), vec![db.get_cell_id(2, vec![]).unwrap()]);
+	assert_eq!(step.get_argument(), Some(LawArgument::Name("q".to_string())));
+	assert_eq!(step.get_result(), db.get_cell_id(4, vec![]).unwrap());
+	assert_eq!(step.get_rule().to_rule_string(), "p q.");
+
+	let mut buffer : Vec<u8> = Vec::new();
+	db.write_proof_log(&mut buffer).unwrap();
+	let string = String::from_utf8(buffer).unwrap();
+	assert_eq!(string, "add_eqt #1@1/0 -> #3@3/ {0 (= 'true' wow)}.\ndeclaration #2@2/ q -> #4@4/ p q.\n");
+	assert_eq!(parse_proof_log(&string).unwrap(), db.get_proof_log().to_vec());
+
+	// the log survives deletion of the derived rule
+	db.delete_rule(3).unwrap();
+	assert_eq!(db.get_proof_log().len(), 2);
+	assert_eq!(db.get_proof_step(3).unwrap().get_law(), Law::Declaration);
+}
+
+#[test]
+fn test_proof_log_arguments() {
+	let mut db = Database::by_string("= 'false' (= 'true' x). {0 (= 'true' (p 0))}.").unwrap();
+	db.scope_creation(CellID::create(1, vec![2]), vec![vec![2]]).unwrap();
+	db.scope_insertion(CellID::create(2, vec![]), Cell::by_string("[=> a b]").unwrap()).unwrap();
+
+	let mut buffer : Vec<u8> = Vec::new();
+	db.write_proof_log(&mut buffer).unwrap();
+	let string = String::from_utf8(buffer).unwrap();
+	assert!(string.starts_with("scope_creation #1@1/2 /2 -> #3@3/ "));
+	assert!(string.contains("scope_insertion #2@2/ [=> a b] -> #4@4/ "));
+	assert_eq!(parse_proof_log(&string).unwrap(), db.get_proof_log().to_vec());
+
+	assert!(parse_proof_log("add_eqt #1/0").is_err());
+	assert!(parse_proof_log("no_law #1/0 -> #2/ a.").is_err());
+	assert!(parse_proof_log("equals_law #1/0 -> #2/ a.").is_err());
+}
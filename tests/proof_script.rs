@@ -0,0 +1,94 @@
+extern crate libsrl;
+use libsrl::db::Database;
+use libsrl::db::script::ProofScript;
+use libsrl::error::LawErrorKind;
+
+static AXIOMS : &'static str = "{0 wow}. = 'false' {0 (= 'false' (p 0))}.";
+
+#[test]
+fn test_replay() {
+	let script = ProofScript::parse("
+		// derive and undo
+		add_eqt #1/0 -> {0 (= 'true' wow)}.
+		rm_eqt #3/0.2 -> {0 wow}.
+
+		declaration #2 q -> p q.
+	").unwrap();
+	assert_eq!(script.get_steps().len(), 3);
+	assert_eq!(script.get_steps()[2].get_line(), 6);
+
+	let mut db = Database::by_string(AXIOMS).unwrap();
+	let report = db.replay(&script);
+	assert!(report.is_success());
+	assert_eq!(report.count_applied(), 3);
+	assert_eq!(db.count_rules(), 6);
+}
+
+#[test]
+fn test_replay_failure_and_mismatch() {
+	let script = ProofScript::parse("
+		add_eqt #1/0 -> {0 (= 'true' nope)}.
+		scope_insertion #2/2 (a b)
+		add_eqt #1/0
+	").unwrap();
+
+	let mut db = Database::by_string(AXIOMS).unwrap();
+	let report = db.replay(&script);
+	assert!(!report.is_success());
+	assert_eq!(report.count_applied(), 1);
+	assert_eq!(report.get_mismatches().len(), 1);
+	assert_eq!(report.get_mismatches()[0].to_string(), "line 2: expected '{0 (= 'true' nope)}.', got '{0 (= 'true' wow)}.'");
+
+	let &(line, ref error) = report.get_failure().unwrap();
+	assert_eq!(line, 3);
+	assert_eq!(error.get_law_error().unwrap().get_kind(), LawErrorKind::WrapperNotPositive);
+}
+
+#[test]
+fn test_proof_log_is_script() {
+	let mut db = Database::by_string(AXIOMS).unwrap();
+	db.add_eqt("1:0").unwrap();
+	db.declaration("2", "q").unwrap();
+	let mut buffer : Vec<u8> = Vec::new();
+	db.write_proof_log(&mut buffer).unwrap();
+
+	let script = ProofScript::parse(&String::from_utf8(buffer).unwrap()).unwrap();
+	let mut replayed = Database::by_string(AXIOMS).unwrap();
+	assert!(replayed.replay(&script).is_success());
+	assert!(replayed == db);
+}
+
+#[test]
+fn test_parse_errors() {
+	assert!(ProofScript::parse("add_eqt").is_err());
+	assert!(ProofScript::parse("add_eqt #1/0 -> wow").is_err());
+	match ProofScript::parse("add_eqt #1/0\nfoo #1") {
+		Err(x) => assert!(x.to_string().starts_with("ProofScript::parse: line 2: ")),
+		Ok(_) => panic!("should fail")
+	}
+}
+
+#[test]
+fn test_replay_log_with_handle_gaps() {
+	use libsrl::navi::CellID;
+
+	let mut db = Database::by_string(AXIOMS).unwrap();
+	db.add_eqt(CellID::create(1, vec![0])).unwrap();
+	db.undo();
+	db.begin_transaction();
+	db.declaration(CellID::create(2, vec![]), "q").unwrap();
+	db.rollback().unwrap();
+	db.add_eqt(CellID::create(2, vec![2])).unwrap(); // handle 5, not 3
+	db.add_eqt(CellID::create(3, vec![])).unwrap(); // uses it
+
+	let mut log = Vec::new();
+	db.write_proof_log(&mut log).unwrap();
+	let script = ProofScript::parse(&String::from_utf8(log).unwrap()).unwrap();
+	assert_eq!(script.get_steps()[0].get_result().unwrap().get_handle().unwrap().get_id(), 5);
+
+	let mut fresh = Database::by_string(AXIOMS).unwrap();
+	let report = fresh.replay(&script);
+	assert!(report.get_failure().is_none(), "{:?}", report.get_failure());
+	assert!(report.is_success());
+	assert!(fresh == db);
+}
@@ -0,0 +1,35 @@
+extern crate libsrl;
+use libsrl::db::Database;
+use libsrl::navi::CellID;
+
+#[test]
+fn test_prune() {
+	let mut db = Database::by_string("{0 wow}. = 'false' (= 'true' x). (= a b). (f a).").unwrap();
+	db.add_eqt(CellID::create(1, vec![0])).unwrap(); // 5 <- 1
+	db.equals_law(CellID::create(4, vec![1]), CellID::create(3, vec![])).unwrap(); // 6 <- 4, 3
+	db.add_eqt(CellID::create(6, vec![])).unwrap(); // 7 <- 6
+	db.rm_eqt(CellID::create(5, vec![0, 2])).unwrap(); // 8 <- 5
+	db.add_eqt(CellID::create(7, vec![])).unwrap(); // 9 <- 7
+	let kept = db.get_rule(7);
+
+	assert!(db.prune(&[10]).is_err());
+	assert_eq!(db.count_rules(), 10);
+
+	assert_eq!(db.prune(&[7]).unwrap(), 3);
+	assert_eq!(db.count_rules(), 7);
+	assert_eq!(db.get_rule(6), kept);
+	assert_eq!(db.get_proof_log().len(), 2);
+	assert!(db.verify_proof_log().is_ok());
+
+	// the proof steps refer to the new positions
+	let step = db.get_proof_step(6).unwrap();
+	assert_eq!(step.get_result(), db.get_cell_id(6, vec![]).unwrap());
+	assert_eq!(step.get_inputs(), vec![db.get_cell_id(5, vec![]).unwrap()]);
+	assert_eq!(db.get_rule_dependencies(6).unwrap(), vec![3, 4, 5]);
+
+	// source rules are never pruned
+	assert_eq!(db.prune(&[]).unwrap(), 2);
+	assert_eq!(db.count_rules(), 5);
+	assert!(db.get_proof_log().is_empty());
+	assert_eq!(db.find_symbol("b"), vec![CellID::create(3, vec![2])]);
+}
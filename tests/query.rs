@@ -0,0 +1,38 @@
+extern crate libsrl;
+use libsrl::db::Database;
+use libsrl::navi::CellID;
+use libsrl::query::Query;
+use libsrl::error::{SRLError, NavigationErrorKind};
+
+#[test]
+fn test_law_by_query() {
+	let mut db = Database::by_string("{0 wow }.").unwrap();
+
+	assert_eq!(db.add_eqt("1 // wow").unwrap().to_rule_string(), "{0 (= 'true' wow)}.");
+	assert_eq!(db.rm_eqt("2:0.2").unwrap().to_rule_string(), "{0 wow}.");
+
+	let query = Query::parse("1:0").unwrap();
+	assert_eq!(db.add_eqt(&query).unwrap().to_rule_string(), "{0 (= 'true' wow)}.");
+}
+
+#[test]
+fn test_law_by_ambiguous_query() {
+	let mut db = Database::by_string("{0 (= 'true' (= wow wow))}.").unwrap();
+
+	match db.add_eqt("1 // wow") {
+		Err(SRLError::Navigation { kind : NavigationErrorKind::QueryAmbiguous { count : 2 }, .. }) => {},
+		x => panic!("unexpected result: {:?}", x)
+	}
+	match db.add_eqt("1 // nothing") {
+		Err(SRLError::Navigation { kind : NavigationErrorKind::QueryNoMatch, .. }) => {},
+		x => panic!("unexpected result: {:?}", x)
+	}
+	assert_eq!(db.count_rules(), 2);
+}
+
+#[test]
+fn test_query_matches() {
+	let db = Database::by_string("{0 (= 'true' (= wow wow))}.").unwrap();
+	assert_eq!(db.query("1 // wow").unwrap(), vec![CellID::create(1, vec![0, 2, 1]), CellID::create(1, vec![0, 2, 2])]);
+	assert_eq!(db.locate("1:0.2").unwrap(), CellID::create(1, vec![0, 2]));
+}
@@ -0,0 +1,36 @@
+extern crate libsrl;
+use libsrl::db::Database;
+use libsrl::error::{SRLError, DatabaseErrorKind};
+
+#[test]
+fn test_handle_survives_deletion() {
+	let mut db = Database::by_string("{0 a}. {0 b}.").unwrap();
+	db.add_eqt(db.get_cell_id(1, vec![0]).unwrap()).unwrap(); // rule 3
+	db.add_eqt(db.get_cell_id(2, vec![0]).unwrap()).unwrap(); // rule 4
+
+	let id = db.get_cell_id(4, vec![0, 2]).unwrap();
+	let handle = id.get_handle().unwrap();
+	db.delete_rule(3).unwrap();
+
+	assert_eq!(db.get_rule_index(handle).unwrap(), 3);
+	assert_eq!(db.get_rule_by_handle(handle).unwrap().to_rule_string(), "{0 (= 'true' b)}.");
+	assert_eq!(db.rm_eqt(id).unwrap().to_rule_string(), "{0 b}.");
+}
+
+#[test]
+fn test_deleted_handle() {
+	let mut db = Database::by_string("{0 a}.").unwrap();
+	db.add_eqt(db.get_cell_id(1, vec![0]).unwrap()).unwrap();
+
+	let id = db.get_cell_id(2, vec![0, 2]).unwrap();
+	db.delete_rule_by_handle(id.get_handle().unwrap()).unwrap();
+	db.add_eqt(db.get_cell_id(1, vec![0]).unwrap()).unwrap(); // takes position 2 again
+
+	match db.rm_eqt(id.clone()) {
+		Err(SRLError::Database { kind : DatabaseErrorKind::UnknownHandle { .. }, .. }) => {},
+		x => panic!("unexpected result: {:?}", x)
+	}
+	assert!(db.get_rule_by_handle(id.get_handle().unwrap()).is_err());
+	assert!(db.delete_rule_by_handle(id.get_handle().unwrap()).is_err());
+	assert_ne!(db.get_rule_handle(2).unwrap(), id.get_handle().unwrap());
+}
@@ -0,0 +1,100 @@
+extern crate libsrl;
+use std::env;
+use std::fs;
+use libsrl::db::Database;
+use libsrl::navi::{CellID, RuleHandle};
+
+#[test]
+fn test_write_and_reload() {
+	let mut db = Database::by_string("{0 wow}. = 'false' {0 (= 'false' (p 0))}.").unwrap();
+	db.add_eqt(CellID::create(1, vec![0])).unwrap();
+	db.declaration(CellID::create(2, vec![]), "q").unwrap();
+
+	let mut buffer : Vec<u8> = Vec::new();
+	db.write_to(&mut buffer).unwrap();
+	let string = String::from_utf8(buffer).unwrap();
+	assert_eq!(string, "{0 wow}.\n= 'false' {0 (= 'false' (p 0))}.\nderived:\n{0 (= 'true' wow)}.\np q.\n");
+
+	let mut reloaded = Database::by_string(&string).unwrap();
+	assert!(reloaded == db);
+	assert!(reloaded.delete_rule(2).is_err()); // still write protected
+	assert!(reloaded.delete_rule(3).is_ok());
+}
+
+#[test]
+fn test_case_cells_survive() {
+	let mut db = Database::by_string("{0 [=> (= 0 a) (p 0)]}.").unwrap();
+	db.add_eqt(CellID::create(1, vec![0, 1])).unwrap();
+
+	let mut buffer : Vec<u8> = Vec::new();
+	db.write_to(&mut buffer).unwrap();
+	assert!(Database::by_string(&String::from_utf8(buffer).unwrap()).unwrap() == db);
+}
+
+#[test]
+fn test_save() {
+	let mut db = Database::by_string("{0 wow}.").unwrap();
+	db.add_eqt(CellID::create(1, vec![0])).unwrap();
+
+	let filename = env::temp_dir().join(format!("libsrl_test_save_{}.srl", std::process::id()));
+	let filename = filename.to_str().unwrap();
+	db.save(filename).unwrap();
+	assert!(fs::metadata(format!("{}.tmp", filename)).is_err());
+	assert!(Database::by_filename(filename).unwrap() == db);
+	fs::remove_file(filename).unwrap();
+	fs::remove_file(format!("{}.proof", filename)).unwrap();
+
+	assert!(db.save("/nonexistent/directory/db.srl").is_err());
+}
+
+#[test]
+fn test_save_proof_log() {
+	let mut db = Database::by_string("{0 wow}. = 'false' (= 'true' x).").unwrap();
+	db.add_eqt(CellID::create(1, vec![0])).unwrap(); // 3
+	db.add_eqt(CellID::create(2, vec![2])).unwrap();
+	db.undo(); // leaves a gap in the handles
+	db.add_eqt(CellID::create(3, vec![0])).unwrap(); // 4 <- 3
+	db.rm_eqt(CellID::create(4, vec![0, 2])).unwrap(); // 5 <- 4
+	db.delete_rule(4).unwrap();
+
+	let filename = env::temp_dir().join(format!("libsrl_test_save_proof_log_{}.srl", std::process::id()));
+	let filename = filename.to_str().unwrap();
+	db.save(filename).unwrap();
+	let mut reloaded = Database::by_filename(filename).unwrap();
+	assert!(reloaded == db);
+	assert_eq!(reloaded.get_proof_log().len(), 3);
+	assert!(reloaded.verify_proof_log().is_ok());
+	// the derived rules are proved again instead of being axioms
+	assert_eq!(reloaded.get_certificate().unwrap().get_axioms().len(), 3);
+	// the deleted rule gets the first handle after the rules
+	assert_eq!(reloaded.get_proof_step(4).unwrap().get_inputs()[0].get_handle(), Some(RuleHandle::create(5)));
+
+	// new rules do not reuse the handle of the deleted rule
+	reloaded.add_eqt(CellID::create(1, vec![0])).unwrap();
+	assert!(reloaded.verify_proof_log().is_ok());
+
+	// a proof file, whose database was not written (e.g. after a crash), is not loaded
+	reloaded.save(filename).unwrap();
+	db.write_to(fs::File::create(filename).unwrap()).unwrap();
+	assert!(Database::by_filename(filename).err().unwrap().to_string().contains("does not belong"));
+
+	// without proof log, the proof file is removed
+	Database::by_string("a.").unwrap().save(filename).unwrap();
+	assert!(fs::metadata(format!("{}.proof", filename)).is_err());
+	assert!(Database::by_filename(filename).unwrap().get_proof_log().is_empty());
+	fs::remove_file(filename).unwrap();
+}
+
+#[test]
+fn test_load_proof_log_mismatch() {
+	let mut db = Database::by_string("{0 wow}.").unwrap();
+	db.add_eqt(CellID::create(1, vec![0])).unwrap();
+	let mut buffer : Vec<u8> = Vec::new();
+	db.write_saved_proof_log(&mut buffer).unwrap();
+
+	let mut other = Database::by_string("{0 wow}.\nderived:\n{0 (= 'true' (= 'true' wow))}.").unwrap();
+	assert!(other.load_proof_log(&buffer[..]).is_err());
+	let mut same = Database::by_string("{0 wow}.\nderived:\n{0 (= 'true' wow)}.").unwrap();
+	same.load_proof_log(&buffer[..]).unwrap();
+	assert!(same.load_proof_log(&buffer[..]).is_err()); // there is a proof log already
+}
@@ -0,0 +1,69 @@
+extern crate libsrl;
+use std::io::{self, Read};
+use libsrl::db::Database;
+use libsrl::db::stream::RuleReader;
+
+#[test]
+fn test_rule_reader() {
+	let source = "a b.\n  {0 (p 0)}\n.  c\nd.\nderived:\n= 'true' a.";
+	let rules : Vec<_> = RuleReader::new(source.as_bytes()).map(|x| x.unwrap()).collect();
+
+	assert_eq!(rules.len(), 4);
+	assert_eq!(rules[1].get_cell().to_rule_string(), "{0 (p 0)}.");
+	assert_eq!(rules[1].get_position().to_string(), "2:3");
+	assert_eq!(rules[2].get_cell().to_rule_string(), "c d.");
+	assert_eq!(rules[2].get_position().to_string(), "3:4");
+	assert!(!rules[2].is_derived());
+	assert!(rules[3].is_derived());
+}
+
+#[test]
+fn test_rule_reader_errors() {
+	let results : Vec<_> = RuleReader::new("a.\n(b.\nc.\nd".as_bytes()).collect();
+	assert_eq!(results.len(), 4);
+	assert!(results[0].is_ok());
+	let srl_error = results[1].as_ref().err().unwrap();
+	assert_eq!(srl_error.to_string(), "RuleReader::next: 2:1: invalid rule");
+	assert!(srl_error.get_source().is_some()); // the error of the parser is kept
+	assert!(results[2].is_ok());
+	assert_eq!(results[3].as_ref().err().unwrap().to_string(), "RuleReader::next: 4:1: rule does not end with '.'");
+
+	assert!(RuleReader::new("a.\nderived:\nb.\nderived:\n".as_bytes()).any(|x| x.is_err()));
+	assert!(RuleReader::new("a\nderived:\nb.".as_bytes()).any(|x| x.is_err()));
+
+	let rules : Vec<_> = RuleReader::new("a.\ngoal:\nb.\nderived:\nc.".as_bytes()).map(|x| x.unwrap()).collect();
+	assert!(rules[1].is_goal() && !rules[1].is_derived());
+	assert!(rules[2].is_derived() && !rules[2].is_goal());
+	assert!(RuleReader::new("goal:\na.\ngoal:\n".as_bytes()).any(|x| x.is_err()));
+}
+
+struct FailingReader;
+
+impl Read for FailingReader {
+	fn read(&mut self, _buf : &mut [u8]) -> io::Result<usize> {
+		Err(io::Error::new(io::ErrorKind::Other, "broken pipe"))
+	}
+}
+
+#[test]
+fn test_from_reader() {
+	let db = Database::from_reader("a b.\n{0 (p 0)}.\nderived:\n= 'true' a.\n".as_bytes()).unwrap();
+	let mut buffer : Vec<u8> = Vec::new();
+	db.write_to(&mut buffer).unwrap();
+	assert!(Database::from_reader(&buffer[..]).unwrap() == db);
+	assert!(Database::by_string(&String::from_utf8(buffer).unwrap()).unwrap() == db);
+	assert_eq!(db.count_rules(), 4);
+
+	assert!(Database::from_reader(FailingReader).is_err());
+	assert!(Database::from_reader("a. (b.".as_bytes()).is_err());
+}
+
+#[test]
+fn test_by_string_uses_reader() {
+	let source = "a.\nderived:\n= 'true' a.\ngoal:\nb.\n";
+	let db = Database::by_string(source).unwrap();
+	assert!(db == Database::from_reader(source.as_bytes()).unwrap());
+	assert_eq!(db.get_goals().len(), 1);
+	let srl_error = Database::by_string("a.\nderived:\nb.\nderived:\n").err().unwrap();
+	assert_eq!(srl_error.to_string(), "RuleReader::next: 4:1: multiple derived sections");
+}
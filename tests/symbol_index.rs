@@ -0,0 +1,31 @@
+extern crate libsrl;
+use libsrl::db::Database;
+use libsrl::navi::CellID;
+
+#[test]
+fn test_symbol_index() {
+	let mut db = Database::by_string("(p a). (p a b). = 'false' {0 (= 'false' (q 0 a))}.").unwrap();
+	assert!(db.contains_cellname("p"));
+	assert!(!db.contains_cellname("r"));
+
+	let stats = db.get_symbol_index().get_stats("p").unwrap();
+	assert_eq!(stats.get_occurrences(), 2);
+	assert_eq!(stats.get_rules(), 2);
+	assert_eq!(stats.get_arities(), vec![(1, 1), (2, 1)]);
+
+	let stats = db.get_symbol_index().get_stats("a").unwrap();
+	assert_eq!(stats.get_occurrences(), 3);
+	assert_eq!(stats.get_arities(), vec![]);
+
+	assert_eq!(db.find_symbol("a"), vec![CellID::create(1, vec![1]), CellID::create(2, vec![1]), CellID::create(3, vec![2, 0, 2, 2])]);
+
+	db.declaration(CellID::create(3, vec![]), "r").unwrap();
+	assert!(db.contains_cellname("r"));
+	assert_eq!(db.find_symbol("r").len(), 1);
+	let rule_id = db.find_symbol("r")[0].get_rule_id();
+
+	db.delete_rule(rule_id).unwrap();
+	assert!(!db.contains_cellname("r"));
+	assert_eq!(db.get_symbol_index().get_stats("a").unwrap().get_occurrences(), 3);
+	assert!(db.get_symbol_index().get_stats("r").is_none());
+}